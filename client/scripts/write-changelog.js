@@ -0,0 +1,28 @@
+#!/usr/bin/env node
+/**
+ * Writes src-tauri/CHANGELOG.md from server/data/changeLog.ts, so the desktop
+ * app can bundle its release history and show it offline.
+ *
+ * Usage: node scripts/write-changelog.js
+ */
+
+const fs = require('fs');
+const path = require('path');
+
+const src = path.join(__dirname, '..', '..', 'server', 'data', 'changeLog.ts');
+const dest = path.join(__dirname, '..', 'src-tauri', 'CHANGELOG.md');
+
+const source = fs.readFileSync(src, 'utf8');
+const array = source.slice(source.indexOf('['), source.lastIndexOf(']') + 1);
+// The file is a plain object literal, so evaluating it is simpler than parsing TypeScript.
+const changeLog = new Function(`return ${array};`)();
+
+const sections = changeLog.map(({ version, date, description, changes }) => {
+  const lines = [`## ${version} (${date})`, ''];
+  if (description) lines.push(description, '');
+  for (const change of changes.filter(Boolean)) lines.push(`- ${change}`);
+  return lines.join('\n').trimEnd();
+});
+
+fs.writeFileSync(dest, `# Changelog\n\n${sections.join('\n\n')}\n`);
+console.log(`Wrote ${changeLog.length} changelog entries to ${path.relative(process.cwd(), dest)}`);
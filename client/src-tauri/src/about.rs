@@ -0,0 +1,31 @@
+#[cfg(target_os = "macos")]
+use tauri::menu::AboutMetadata;
+use tauri::AppHandle;
+#[cfg(not(target_os = "macos"))]
+use tauri_plugin_dialog::DialogExt;
+
+use crate::{COPYRIGHT, PROJECT_URL};
+
+// Populates the native macOS about panel.
+#[cfg(target_os = "macos")]
+pub fn metadata(app: &AppHandle) -> AboutMetadata<'static> {
+    let info = app.package_info();
+    AboutMetadata {
+        name: Some(info.name.clone()),
+        version: Some(info.version.to_string()),
+        copyright: Some(COPYRIGHT.into()),
+        website: Some(PROJECT_URL.into()),
+        website_label: Some("GitHub".into()),
+        ..Default::default()
+    }
+}
+
+// Windows and Linux have no native about panel, so show the same details in a dialog.
+#[cfg(not(target_os = "macos"))]
+pub fn show_dialog(app: &AppHandle) {
+    let info = app.package_info();
+    app.dialog()
+        .message(format!("Version {}\n\n{}\n{}", info.version, COPYRIGHT, PROJECT_URL))
+        .title(format!("About {}", info.name))
+        .show(|_| {});
+}
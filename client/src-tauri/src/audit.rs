@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::Invoke;
+use tauri::{Manager, Runtime};
+
+// Log target for IPC audit records; `logging` routes it to its own file.
+pub const TARGET: &str = "ipc";
+
+// Minimum gap between calls to commands that are expensive or hit the network.
+const RATE_LIMITS: [(&str, Duration); 4] = [
+    ("clear_cache", Duration::from_secs(10)),
+    ("update_status", Duration::from_secs(10)),
+    ("update_details", Duration::from_secs(10)),
+    ("ping_update_server", Duration::from_secs(5)),
+];
+
+// When each rate-limited command was last let through.
+#[derive(Default)]
+pub struct LastCalls(Mutex<HashMap<&'static str, Instant>>);
+
+// Returns how long to wait if `command` was called too recently, else records the call.
+fn rate_limited(last_calls: &LastCalls, command: &str) -> Option<Duration> {
+    let (name, limit) = RATE_LIMITS.iter().find(|(name, _)| *name == command)?;
+    let mut last_calls = last_calls.0.lock().unwrap();
+    let now = Instant::now();
+    if let Some(elapsed) = last_calls.get(name).map(|last| now.duration_since(*last)) {
+        if elapsed < *limit {
+            return Some(*limit - elapsed);
+        }
+    }
+    last_calls.insert(name, now);
+    None
+}
+
+// Wraps the generated command handler: records every invocation (command, calling
+// window, dispatch time), rejects calls from in-app browser windows and rate-limited
+// commands called too often. Async commands are timed up to the point they're spawned.
+pub fn dispatch<R: Runtime>(invoke: Invoke<R>, handler: &impl Fn(Invoke<R>) -> bool) -> bool {
+    let command = invoke.message.command().to_string();
+    let window = invoke.message.webview().label().to_string();
+
+    // Pages in in-app browser windows are arbitrary websites.
+    #[cfg(desktop)]
+    if window.starts_with(crate::windows::BROWSER_WINDOW_PREFIX) {
+        log::warn!(target: TARGET, "{} from {} rejected: in-app browser windows can't call app commands", command, window);
+        invoke.resolver.reject(format!("{} isn't available to in-app browser windows", command));
+        return true;
+    }
+
+    // Not managed until setup runs; nothing the frontend calls arrives before then.
+    let wait = invoke.message.webview()
+        .try_state::<LastCalls>()
+        .and_then(|last_calls| rate_limited(&last_calls, &command));
+    if let Some(wait) = wait {
+        log::warn!(target: TARGET, "{} from {} rate limited ({:?} remaining)", command, window, wait);
+        invoke.resolver.reject(format!("{} was called too recently; try again in {}s", command, wait.as_secs() + 1));
+        return true;
+    }
+
+    let started = Instant::now();
+    let handled = handler(invoke);
+    log::info!(target: TARGET, "{} from {} took {:?}{}", command, window, started.elapsed(), if handled { "" } else { " (unknown command)" });
+    handled
+}
@@ -0,0 +1,59 @@
+use tauri::AppHandle;
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::{menu, settings};
+
+pub const MENU_ID: &str = "launch_at_login";
+
+// Registers or removes the login item. The OS entry is checked first so toggling
+// repeatedly never stacks up duplicate entries.
+fn apply(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    if autolaunch.is_enabled().map_err(|e| e.to_string())? == enabled {
+        return Ok(());
+    }
+    if enabled {
+        autolaunch.enable().map_err(|e| e.to_string())
+    } else {
+        autolaunch.disable().map_err(|e| e.to_string())
+    }
+}
+
+fn sync_menu(app: &AppHandle, enabled: bool) {
+    if let Some(item) = menu::check_item(app, MENU_ID) {
+        let _ = item.set_checked(enabled);
+    }
+}
+
+pub fn set(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let result = apply(app, enabled).and_then(|()| {
+        settings::update(app, |settings| settings.launch_at_login = enabled)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    });
+    // Reflect what's actually registered, whether or not the change went through.
+    sync_menu(app, is_enabled(app));
+    result
+}
+
+pub fn is_enabled(app: &AppHandle) -> bool {
+    app.autolaunch().is_enabled().unwrap_or(false)
+}
+
+// Brings the OS login item back in line with settings, e.g. after the user removed
+// it by hand or the app moved.
+pub fn restore(app: &AppHandle) {
+    if let Err(e) = apply(app, settings::current(app).launch_at_login) {
+        log::warn!("Failed to sync launch at login: {}", e);
+    }
+}
+
+#[tauri::command]
+pub fn set_autostart(app: AppHandle, enabled: bool) -> Result<(), String> {
+    set(&app, enabled)
+}
+
+#[tauri::command]
+pub fn get_autostart(app: AppHandle) -> bool {
+    is_enabled(&app)
+}
@@ -0,0 +1,47 @@
+use tauri::{AppHandle, Manager};
+
+// Diameter of the Windows taskbar overlay, which can't show a number.
+#[cfg(windows)]
+const OVERLAY_SIZE: u32 = 16;
+
+// A plain red dot, drawn here rather than shipped as an icon asset.
+#[cfg(windows)]
+fn overlay_dot() -> tauri::image::Image<'static> {
+    let radius = OVERLAY_SIZE as f32 / 2.0;
+    let mut rgba = Vec::with_capacity((OVERLAY_SIZE * OVERLAY_SIZE * 4) as usize);
+    for y in 0..OVERLAY_SIZE {
+        for x in 0..OVERLAY_SIZE {
+            let (dx, dy) = (x as f32 + 0.5 - radius, y as f32 + 0.5 - radius);
+            let alpha = if dx * dx + dy * dy <= radius * radius { 255 } else { 0 };
+            rgba.extend_from_slice(&[220, 38, 38, alpha]);
+        }
+    }
+    tauri::image::Image::new_owned(rgba, OVERLAY_SIZE, OVERLAY_SIZE)
+}
+
+// Badges the app icon: the Dock badge on macOS, a taskbar overlay on Windows.
+// `None` or zero clears it. Linux has no portable equivalent.
+pub fn set(app: &AppHandle, count: Option<u32>) {
+    let count = count.filter(|&count| count > 0);
+    let Some(window) = app.get_webview_window("main") else { return };
+
+    #[cfg(target_os = "macos")]
+    let result = window.set_badge_count(count.map(i64::from));
+    #[cfg(windows)]
+    let result = window.set_overlay_icon(count.map(|_| overlay_dot()));
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let result: tauri::Result<()> = {
+        let _ = window;
+        log::debug!("App badges aren't supported on this platform; ignoring {:?}", count);
+        Ok(())
+    };
+
+    if let Err(e) = result {
+        log::warn!("Failed to set app badge: {}", e);
+    }
+}
+
+#[tauri::command]
+pub fn set_badge(app: AppHandle, count: Option<u32>) {
+    set(&app, count);
+}
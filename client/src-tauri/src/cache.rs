@@ -0,0 +1,43 @@
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+// Deletes everything under `dir`, returning the bytes freed. Entries that can't be
+// removed (permissions, in use) are logged and skipped so one bad file doesn't stop
+// the rest.
+fn clear_dir(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else { return 0 };
+    let mut freed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(meta) = entry.metadata() else { continue };
+        if meta.is_dir() {
+            freed += clear_dir(&path);
+            if let Err(e) = fs::remove_dir(&path) {
+                log::warn!("Couldn't remove cache dir {}: {}", path.display(), e);
+            }
+        } else {
+            match fs::remove_file(&path) {
+                Ok(()) => freed += meta.len(),
+                Err(e) => log::warn!("Couldn't remove cache file {}: {}", path.display(), e),
+            }
+        }
+    }
+    freed
+}
+
+// Empties the app cache dir only; config and data dirs are never touched.
+pub fn clear(app: &AppHandle) -> Result<u64, String> {
+    let dir = app.path().app_cache_dir().map_err(|e| e.to_string())?;
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let freed = clear_dir(&dir);
+    log::info!("Cleared {} bytes from {}", freed, dir.display());
+    Ok(freed)
+}
+
+#[tauri::command]
+pub fn clear_cache(app: AppHandle) -> Result<u64, String> {
+    clear(&app)
+}
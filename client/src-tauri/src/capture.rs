@@ -0,0 +1,57 @@
+use std::io::Cursor;
+use tauri::Window;
+use xcap::image::{imageops, DynamicImage, ImageFormat};
+
+// Tauri has no webview snapshot API, so this captures the native window and crops
+// off the frame, which leaves what the webview is showing. On macOS this needs the
+// Screen Recording permission; on Wayland window capture isn't supported at all.
+// Windows can share a title, so `origin` (the outer position) picks between them.
+fn capture_png(title: &str, origin: Option<(i32, i32)>, crop: Option<(u32, u32, u32, u32)>) -> Result<Vec<u8>, String> {
+    let windows = xcap::Window::all().map_err(|e| format!("Window capture isn't available: {}", e))?;
+    let mut candidates: Vec<_> = windows
+        .into_iter()
+        .filter(|window| window.title() == title && !window.is_minimized())
+        .collect();
+    let index = candidates.iter().position(|window| Some((window.x(), window.y())) == origin).unwrap_or(0);
+    if candidates.is_empty() {
+        return Err("Couldn't find the window to capture (is it minimized?)".into());
+    }
+    let target = candidates.swap_remove(index);
+    let mut image = target.capture_image().map_err(|e| {
+        if cfg!(target_os = "macos") {
+            format!("Couldn't capture the window ({}). Allow Screen Recording in System Settings > Privacy & Security.", e)
+        } else {
+            format!("Couldn't capture the window: {}", e)
+        }
+    })?;
+    if let Some((x, y, width, height)) = crop {
+        if x + width <= image.width() && y + height <= image.height() {
+            image = imageops::crop_imm(&image, x, y, width, height).to_image();
+        }
+    }
+
+    let mut png = Vec::new();
+    DynamicImage::ImageRgba8(image)
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(png)
+}
+
+// The content area relative to the window's outer frame, in physical pixels.
+fn content_bounds(window: &Window) -> Option<(u32, u32, u32, u32)> {
+    let outer = window.outer_position().ok()?;
+    let inner = window.inner_position().ok()?;
+    let size = window.inner_size().ok()?;
+    Some(((inner.x - outer.x).max(0) as u32, (inner.y - outer.y).max(0) as u32, size.width, size.height))
+}
+
+// PNG bytes of `window`'s contents, for attaching to bug reports.
+#[tauri::command]
+pub async fn capture_window(window: Window) -> Result<Vec<u8>, String> {
+    let title = window.title().map_err(|e| e.to_string())?;
+    let origin = window.outer_position().ok().map(|position| (position.x, position.y));
+    let crop = content_bounds(&window);
+    tauri::async_runtime::spawn_blocking(move || capture_png(&title, origin, crop))
+        .await
+        .map_err(|e| e.to_string())?
+}
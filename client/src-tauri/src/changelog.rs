@@ -0,0 +1,69 @@
+use std::fs;
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Manager};
+
+use crate::error::{CommandError, ErrorCode};
+
+// Bundled by `tauri.conf.json`; regenerated from the server's changelog by
+// `scripts/write-changelog.js` before every dev run and release build. The copy in
+// git is only there so plain `cargo build` finds the resource.
+const CHANGELOG_RESOURCE: &str = "CHANGELOG.md";
+
+// The section under `## <version>`, heading included, up to the next `## `.
+// The version may be written bare, as `v1.2.3`, or as `[1.2.3]`.
+fn entry<'a>(changelog: &'a str, version: &str) -> Option<&'a str> {
+    let version = version.trim_start_matches('v');
+    let mut start = None;
+    let mut offset = 0;
+    for line in changelog.split_inclusive('\n') {
+        if let Some(heading) = line.strip_prefix("## ") {
+            if start.is_some() {
+                return start.map(|start| changelog[start..offset].trim_end());
+            }
+            let heading_version = heading.split_whitespace().next().unwrap_or("");
+            if heading_version.trim_matches(['[', ']']).trim_start_matches('v') == version {
+                start = Some(offset);
+            }
+        }
+        offset += line.len();
+    }
+    start.map(|start| changelog[start..].trim_end())
+}
+
+// The bundled release history, or just `version`'s entry when one is given.
+#[tauri::command]
+pub fn changelog(app: AppHandle, version: Option<String>) -> Result<String, CommandError> {
+    let path = app
+        .path()
+        .resolve(CHANGELOG_RESOURCE, BaseDirectory::Resource)
+        .map_err(|e| CommandError::new(ErrorCode::NotFound, format!("Couldn't locate the bundled changelog: {}", e)))?;
+    let contents = fs::read_to_string(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => CommandError::new(ErrorCode::NotFound, "This build doesn't include a changelog."),
+        _ => CommandError::io(&e, format!("Couldn't read the bundled changelog: {}", e)),
+    })?;
+    match version {
+        Some(version) => entry(&contents, &version).map(str::to_string).ok_or_else(|| {
+            CommandError::new(ErrorCode::NotFound, format!("The changelog has no entry for version {}.", version))
+        }),
+        None => Ok(contents),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "# Changelog\n\n## 1.2.0 (2026-02-01)\n\nNew minor\n\n- two\n\n## [1.1.0] - 2026-01-01\n\n- one\n";
+
+    #[test]
+    fn finds_entry_up_to_next_heading() {
+        assert_eq!(entry(CHANGELOG, "1.2.0"), Some("## 1.2.0 (2026-02-01)\n\nNew minor\n\n- two"));
+        assert_eq!(entry(CHANGELOG, "v1.1.0"), Some("## [1.1.0] - 2026-01-01\n\n- one"));
+    }
+
+    #[test]
+    fn missing_version_has_no_entry() {
+        assert_eq!(entry(CHANGELOG, "1.0.0"), None);
+        assert_eq!(entry(CHANGELOG, "1.2"), None);
+    }
+}
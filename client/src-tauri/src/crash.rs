@@ -0,0 +1,79 @@
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+use std::sync::{Once, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+const CRASH_FILE: &str = "last-crash.txt";
+// Where a report goes once the frontend has been handed it.
+const SEEN_CRASH_FILE: &str = "last-crash.seen.txt";
+
+// Set once the app log dir is known; panics before that only reach stderr.
+static CRASH_PATH: OnceLock<PathBuf> = OnceLock::new();
+static INSTALL: Once = Once::new();
+
+fn report(info: &PanicHookInfo) -> String {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic payload".into());
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_else(|| "unknown location".into());
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+
+    format!(
+        "Angular Momentum {} crashed\nOS: {} ({})\nTime: {} (unix)\nThread: {}\nPanic: {}\nAt: {}\n\nBacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        timestamp,
+        std::thread::current().name().unwrap_or("unnamed"),
+        message,
+        location,
+        Backtrace::force_capture(),
+    )
+}
+
+// Writes a crash report for every panic, then hands over to the previous hook so
+// the default stderr output (and anything Tauri relies on) still happens. Only the
+// file is written: the panic may have happened with the logger's lock held.
+pub fn install_panic_hook() {
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if let Some(path) = CRASH_PATH.get() {
+                let _ = fs::write(path, report(info));
+            }
+            previous(info);
+        }));
+    });
+}
+
+// Points crash reports at the app log dir; called from `setup`.
+pub fn init(app: &AppHandle) {
+    match app.path().app_log_dir() {
+        Ok(dir) => {
+            let _ = fs::create_dir_all(&dir);
+            let _ = CRASH_PATH.set(dir.join(CRASH_FILE));
+        }
+        Err(e) => log::warn!("No log dir for crash reports: {}", e),
+    }
+}
+
+// The report from the most recent crash, if one was recorded and hasn't been
+// returned before. It's moved aside once read, so it's offered only once.
+#[tauri::command]
+pub fn get_last_crash() -> Option<String> {
+    let path = CRASH_PATH.get()?;
+    let report = fs::read_to_string(path).ok()?;
+    if let Err(e) = fs::rename(path, path.with_file_name(SEEN_CRASH_FILE)) {
+        log::warn!("Failed to mark crash report as seen: {}", e);
+    }
+    Some(report)
+}
@@ -0,0 +1,309 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{App, AppHandle, Emitter, Manager, State, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+#[cfg(desktop)]
+use crate::error::{CommandError, ErrorCode};
+use crate::events::{self, NavigateRequest};
+
+pub const SCHEME: &str = "angmom";
+const WEB_HOST: &str = "angularmomentum.app";
+// Hosts with their own meaning; anything else on the scheme is a plain route.
+const ACTION_HOSTS: [&str; 4] = ["open-settings", "install-update", "navigate", "open"];
+
+// What a deep link asks for. Anything beyond plain navigation is carried out here,
+// on the native side, so a link can't make the frontend do more than it should.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLinkAction {
+    // angmom://open-settings
+    OpenSettings,
+    // angmom://install-update (runs the normal, confirmed update check)
+    InstallUpdate,
+    // A frontend route, e.g. "/settings/profile?tab=2".
+    Navigate(String),
+}
+
+// The link the app was cold-started with, held until the frontend asks for it;
+// an event emitted during setup would fire before any listener exists.
+#[derive(Default)]
+pub struct PendingDeepLink(Mutex<Option<NavigateRequest>>);
+
+// Re-encodes the decoded pairs, so an encoded `&`, `#` or `?` in a value can't add
+// parameters or a fragment to the route. Spaces go out as `%20`: the serializer
+// writes them as `+`, which the frontend router would keep literally, and it
+// encodes a real `+` as `%2B`, so every `+` left is a space.
+fn with_query(path: String, query: &[(String, String)]) -> String {
+    if query.is_empty() {
+        return path;
+    }
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(query)
+        .finish()
+        .replace('+', "%20");
+    format!("{}?{}", path, query)
+}
+
+// Builds a route from a path, rejecting traversal segments. A decoded `view` may
+// hold `?` or `#`, which would start a query or fragment, so those are refused too.
+fn route(path: &str, query: &[(String, String)]) -> Option<String> {
+    let path = path.trim_matches('/');
+    if path.is_empty() || path.contains(['?', '#']) || path.split('/').any(|segment| segment == "..") {
+        return None;
+    }
+    Some(with_query(format!("/{}", path), query))
+}
+
+// The routing table:
+//   angmom://open-settings          -> OpenSettings
+//   angmom://install-update         -> InstallUpdate
+//   angmom://navigate/settings/x    -> Navigate("/settings/x")
+//   angmom://open?view=settings     -> Navigate("/settings")
+//   angmom://settings/profile       -> Navigate("/settings/profile")
+//   https://angularmomentum.app/x   -> Navigate("/x")
+// Anything else is rejected.
+fn parse_action(url: &Url) -> Option<DeepLinkAction> {
+    match (url.scheme(), url.host_str()) {
+        (SCHEME, Some("open-settings")) if url.path().trim_matches('/').is_empty() => Some(DeepLinkAction::OpenSettings),
+        (SCHEME, Some("install-update")) if url.path().trim_matches('/').is_empty() => Some(DeepLinkAction::InstallUpdate),
+        (SCHEME, Some("navigate")) => {
+            let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+            route(url.path(), &query).map(DeepLinkAction::Navigate)
+        }
+        (SCHEME, Some("open")) => {
+            let mut view = None;
+            let mut rest = Vec::new();
+            for (key, value) in url.query_pairs() {
+                if key == "view" {
+                    view = Some(value.into_owned());
+                } else {
+                    rest.push((key.into_owned(), value.into_owned()));
+                }
+            }
+            route(&view?, &rest).map(DeepLinkAction::Navigate)
+        }
+        // Links from before the action hosts existed, e.g. angmom://settings/profile.
+        (SCHEME, Some(host)) if !ACTION_HOSTS.contains(&host) => {
+            let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+            route(&format!("{}{}", host, url.path()), &query).map(DeepLinkAction::Navigate)
+        }
+        ("https", Some(WEB_HOST)) => {
+            let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+            // The site root is a valid target, unlike an empty app route.
+            match url.path().trim_matches('/') {
+                "" => Some(DeepLinkAction::Navigate(with_query("/".into(), &query))),
+                path => route(path, &query).map(DeepLinkAction::Navigate),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn to_action(url: &Url) -> Option<DeepLinkAction> {
+    let action = parse_action(url);
+    if action.is_none() {
+        log::warn!("Rejecting unknown or malformed deep link: {}", url);
+    }
+    action
+}
+
+fn perform(app: &AppHandle, url: &Url, action: DeepLinkAction) {
+    log::info!("Deep link {} -> {:?}", url, action);
+    match action {
+        DeepLinkAction::Navigate(route) => {
+            let _ = app.emit(events::DEEP_LINK_NAVIGATE, NavigateRequest { url: url.to_string(), route });
+        }
+        #[cfg(desktop)]
+        DeepLinkAction::OpenSettings => {
+            if let Err(e) = crate::windows::open_preferences(app) {
+                log::error!("Failed to open preferences window: {}", e);
+            }
+        }
+        #[cfg(desktop)]
+        DeepLinkAction::InstallUpdate => {
+            tauri::async_runtime::spawn(crate::updater::check_for_updates(app.clone(), true));
+        }
+        #[cfg(mobile)]
+        DeepLinkAction::OpenSettings => {
+            let _ = app.emit(events::DEEP_LINK_NAVIGATE, NavigateRequest { url: url.to_string(), route: "/settings".into() });
+        }
+        #[cfg(mobile)]
+        DeepLinkAction::InstallUpdate => log::info!("Ignoring install-update link; mobile updates go through the store"),
+    }
+}
+
+pub fn handle_url(app: &AppHandle, url: &Url) {
+    if let Some(action) = to_action(url) {
+        perform(app, url, action);
+    }
+}
+
+// The custom schemes in tauri.conf.json's deep-link config.
+#[cfg(desktop)]
+fn configured_schemes(app: &AppHandle) -> Vec<String> {
+    let schemes: Vec<String> = app.config().plugins.0.get("deep-link")
+        .and_then(|config| config.get("desktop"))
+        .and_then(|desktop| desktop.get("schemes"))
+        .and_then(|schemes| schemes.as_array())
+        .map(|schemes| schemes.iter().filter_map(|scheme| scheme.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    if schemes.is_empty() {
+        vec![SCHEME.to_string()]
+    } else {
+        schemes
+    }
+}
+
+// Outcome of checking one scheme in `repair_schemes`.
+#[cfg(desktop)]
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemeStatus {
+    pub scheme: String,
+    pub registered: bool,
+    pub error: Option<String>,
+}
+
+// Re-registers any configured scheme the OS has lost. Only Windows and Linux allow
+// this at runtime; macOS registers schemes from the app bundle's Info.plist, so
+// there an unregistered scheme means the app needs reinstalling.
+#[cfg(desktop)]
+pub fn repair(app: &AppHandle) -> Vec<SchemeStatus> {
+    configured_schemes(app)
+        .into_iter()
+        .map(|scheme| {
+            let result = match app.deep_link().is_registered(&scheme) {
+                Ok(true) => Ok(()),
+                Ok(false) => {
+                    log::warn!("{}:// isn't registered to this app; registering it", scheme);
+                    app.deep_link().register(&scheme).map_err(|e| e.to_string())
+                }
+                Err(e) => Err(e.to_string()),
+            };
+            match &result {
+                Ok(()) => log::info!("{}:// is registered", scheme),
+                Err(e) => log::warn!("Couldn't verify or register {}://: {}", scheme, e),
+            }
+            SchemeStatus { scheme, registered: result.is_ok(), error: result.err() }
+        })
+        .collect()
+}
+
+// Configured schemes the OS currently routes to this app. macOS can't be asked,
+// so there it fails with `UNSUPPORTED` rather than reporting no schemes.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn registered_schemes(app: AppHandle) -> Result<Vec<String>, CommandError> {
+    if cfg!(target_os = "macos") {
+        return Err(CommandError::new(
+            ErrorCode::Unsupported,
+            "macOS registers link schemes from the app bundle and can't report them at runtime.",
+        ));
+    }
+    Ok(configured_schemes(&app)
+        .into_iter()
+        .filter(|scheme| app.deep_link().is_registered(scheme).unwrap_or(false))
+        .collect())
+}
+
+#[cfg(desktop)]
+#[tauri::command]
+pub fn repair_schemes(app: AppHandle) -> Vec<SchemeStatus> {
+    repair(&app)
+}
+
+// Runs at the end of `setup`, so everything a deep link can act on already exists.
+pub fn init(app: &App) {
+    app.manage(PendingDeepLink::default());
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    repair(app.handle());
+
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => {
+            if let Some((url, action)) = urls.iter().find_map(|url| to_action(url).map(|action| (url, action))) {
+                match action {
+                    // Held for the frontend, which isn't listening yet.
+                    DeepLinkAction::Navigate(route) => {
+                        *app.state::<PendingDeepLink>().0.lock().unwrap() =
+                            Some(NavigateRequest { url: url.to_string(), route });
+                    }
+                    action => perform(app.handle(), url, action),
+                }
+            }
+        }
+        Ok(None) => {}
+        Err(e) => log::error!("Failed to read launch deep link: {}", e),
+    }
+
+    let handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle_url(&handle, &url);
+        }
+    });
+}
+
+// Returns (and clears) the deep link the app was launched with, if any.
+#[tauri::command]
+pub fn take_pending_deep_link(pending: State<'_, PendingDeepLink>) -> Option<NavigateRequest> {
+    pending.0.lock().unwrap().take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> Option<DeepLinkAction> {
+        parse_action(&Url::parse(url).unwrap())
+    }
+
+    fn navigate(route: &str) -> Option<DeepLinkAction> {
+        Some(DeepLinkAction::Navigate(route.into()))
+    }
+
+    #[test]
+    fn parses_actions() {
+        assert_eq!(parse("angmom://open-settings"), Some(DeepLinkAction::OpenSettings));
+        assert_eq!(parse("angmom://install-update/"), Some(DeepLinkAction::InstallUpdate));
+    }
+
+    #[test]
+    fn parses_navigation() {
+        assert_eq!(parse("angmom://navigate/settings/profile"), navigate("/settings/profile"));
+        assert_eq!(parse("angmom://navigate/features?tab=2"), navigate("/features?tab=2"));
+        assert_eq!(parse("angmom://open?view=settings&tab=2"), navigate("/settings?tab=2"));
+        assert_eq!(parse("https://angularmomentum.app/privacy"), navigate("/privacy"));
+        assert_eq!(parse("https://angularmomentum.app/"), navigate("/"));
+    }
+
+    #[test]
+    fn query_values_stay_encoded() {
+        assert_eq!(parse("angmom://navigate/x?next=a%26b"), navigate("/x?next=a%26b"));
+        assert_eq!(parse("angmom://navigate/x?next=a%23b"), navigate("/x?next=a%23b"));
+        assert_eq!(parse("angmom://navigate/x?next=a%3Fb%3Dc"), navigate("/x?next=a%3Fb%3Dc"));
+        assert_eq!(parse("angmom://navigate/x?q=a+b%2Bc"), navigate("/x?q=a%20b%2Bc"));
+        assert_eq!(parse("angmom://open?view=settings&next=%2Fhome%23top"), navigate("/settings?next=%2Fhome%23top"));
+    }
+
+    #[test]
+    fn plain_scheme_links_still_navigate() {
+        assert_eq!(parse("angmom://settings/profile"), navigate("/settings/profile"));
+        assert_eq!(parse("angmom://features?tab=2"), navigate("/features?tab=2"));
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        assert_eq!(parse("angmom://"), None);
+        assert_eq!(parse("angmom://install-update/now"), None);
+        assert_eq!(parse("angmom://navigate"), None);
+        assert_eq!(parse("angmom://navigate/"), None);
+        assert_eq!(parse("angmom://open"), None);
+        assert_eq!(parse("angmom://open?view="), None);
+        assert_eq!(parse("angmom://open?view=../secrets"), None);
+        assert_eq!(parse("angmom://open?view=settings%3Ftab%3D2"), None);
+        assert_eq!(parse("angmom://open?view=settings%23top"), None);
+        assert_eq!(parse("https://evil.example/settings"), None);
+        assert_eq!(parse("http://angularmomentum.app/settings"), None);
+        assert_eq!(parse("file:///etc/passwd"), None);
+        assert_eq!(parse("javascript:alert(1)"), None);
+    }
+}
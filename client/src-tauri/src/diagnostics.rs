@@ -0,0 +1,119 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_notification::NotificationExt;
+
+// Everything a bug report needs to identify the build and the machine it ran on.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlatformInfo {
+    pub os: String,
+    pub os_version: String,
+    pub arch: String,
+    pub tauri_version: String,
+    pub app_version: String,
+    pub webview_version: Option<String>,
+    pub locale: Option<String>,
+}
+
+impl PlatformInfo {
+    // Plain-text form for pasting into an issue.
+    pub fn to_report(&self) -> String {
+        let unknown = || "unknown".to_string();
+        format!(
+            "Angular Momentum {}\nOS: {} {} ({})\nTauri: {}\nWebView: {}\nLocale: {}",
+            self.app_version,
+            self.os,
+            self.os_version,
+            self.arch,
+            self.tauri_version,
+            self.webview_version.clone().unwrap_or_else(unknown),
+            self.locale.clone().unwrap_or_else(unknown),
+        )
+    }
+}
+
+pub fn collect(app: &AppHandle) -> PlatformInfo {
+    PlatformInfo {
+        os: std::env::consts::OS.to_string(),
+        os_version: tauri_plugin_os::version().to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        tauri_version: tauri::VERSION.to_string(),
+        app_version: app.package_info().version.to_string(),
+        webview_version: tauri::webview_version().ok(),
+        locale: tauri_plugin_os::locale(),
+    }
+}
+
+#[tauri::command]
+pub fn platform_info(app: AppHandle) -> PlatformInfo {
+    collect(&app)
+}
+
+#[tauri::command]
+pub fn copy_diagnostics(app: AppHandle) -> Result<(), String> {
+    app.clipboard()
+        .write_text(collect(&app).to_report())
+        .map_err(|e| e.to_string())?;
+    let _ = app.notification()
+        .builder()
+        .title("Diagnostics Copied")
+        .body("Version and platform info is on the clipboard, ready to paste into a bug report.")
+        .show();
+    Ok(())
+}
+
+// One row of `plugin_health`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginStatus {
+    pub name: &'static str,
+    // `None` when there's nothing that shows whether the plugin loaded.
+    pub ok: Option<bool>,
+    pub message: String,
+}
+
+impl PluginStatus {
+    fn new(name: &'static str, result: Result<String, String>) -> Self {
+        match result {
+            Ok(message) => Self { name, ok: Some(true), message },
+            Err(message) => Self { name, ok: Some(false), message },
+        }
+    }
+
+    fn unchecked(name: &'static str, message: &str) -> Self {
+        Self { name, ok: None, message: message.into() }
+    }
+}
+
+// A plugin that registered keeps its handle in managed state; finding it there is
+// the most that can be checked without side effects.
+fn registered<T: Send + Sync + 'static>(app: &AppHandle) -> Result<String, String> {
+    match app.try_state::<T>() {
+        Some(_) => Ok("Loaded".into()),
+        None => Err("Not loaded; the plugin failed to register".into()),
+    }
+}
+
+// Whether each plugin the app depends on actually loaded, for builds where one
+// failed quietly (the updater, for instance, only logs a warning).
+#[tauri::command]
+pub fn plugin_health(app: AppHandle) -> Vec<PluginStatus> {
+    let notification = registered::<tauri_plugin_notification::Notification<Wry>>(&app).and_then(|_| {
+        app.notification()
+            .permission_state()
+            .map(|state| format!("Loaded; permission {}", format!("{:?}", state).to_lowercase()))
+            .map_err(|e| format!("Loaded, but permission state unavailable: {}", e))
+    });
+    #[cfg(desktop)]
+    let updater = crate::updater::health(&app).map(|endpoint| format!("Ready; checking {}", endpoint));
+    #[cfg(mobile)]
+    let updater = Err("Not available on mobile; updates come from the app store".to_string());
+    vec![
+        PluginStatus::new("updater", updater),
+        PluginStatus::new("dialog", registered::<tauri_plugin_dialog::Dialog<Wry>>(&app)),
+        PluginStatus::new("opener", registered::<tauri_plugin_opener::Opener<Wry>>(&app)),
+        PluginStatus::new("notification", notification),
+        PluginStatus::new("deep-link", registered::<tauri_plugin_deep_link::DeepLink<Wry>>(&app)),
+        // Only adds commands and keeps no state, so there's nothing to look for.
+        PluginStatus::unchecked("process", "Can't be checked; the plugin keeps no state"),
+    ]
+}
@@ -0,0 +1,96 @@
+// macOS Dock menu. Tauri has no API for it, so `applicationDockMenu:` is added to
+// the app delegate at runtime. Items are dispatched through `handle_menu_event`,
+// the same path the app menu takes.
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, Sel};
+use objc2::sel;
+use objc2_app_kit::{NSApplication, NSMenu, NSMenuItem};
+use objc2_foundation::{MainThreadMarker, NSString};
+use tauri::AppHandle;
+
+// Menu ids from `menu::create`, in Dock order. An item's tag is its index here.
+const ITEMS: [(&str, &str); 2] = [("new_window", "New Window"), ("check_updates", "Check for Updates...")];
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+thread_local! {
+    // AppKit only touches the Dock menu on the main thread.
+    static DOCK_MENU: RefCell<Option<Retained<NSMenu>>> = const { RefCell::new(None) };
+}
+
+extern "C" fn dock_menu(_this: &AnyObject, _cmd: Sel, _app: &AnyObject) -> *mut NSMenu {
+    DOCK_MENU.with(|menu| {
+        menu.borrow()
+            .as_ref()
+            .map_or(std::ptr::null_mut(), |menu| Retained::as_ptr(menu) as *mut NSMenu)
+    })
+}
+
+extern "C" fn dock_item_selected(_this: &AnyObject, _cmd: Sel, sender: &NSMenuItem) {
+    let tag = unsafe { sender.tag() };
+    let (Some(app), Some((id, _))) = (APP.get(), usize::try_from(tag).ok().and_then(|tag| ITEMS.get(tag))) else {
+        return;
+    };
+    crate::handle_menu_event(app, id);
+}
+
+// Must run on the main thread, after tao has installed its app delegate (i.e. in `setup`).
+pub fn create(app: &AppHandle) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        log::warn!("Dock menu must be created on the main thread");
+        return;
+    };
+    let _ = APP.set(app.clone());
+
+    let ns_app = NSApplication::sharedApplication(mtm);
+    let Some(app_delegate) = (unsafe { ns_app.delegate() }) else {
+        log::warn!("No app delegate; skipping Dock menu");
+        return;
+    };
+    let delegate: &AnyObject = (*app_delegate).as_ref();
+
+    unsafe {
+        let class = delegate.class() as *const _ as *mut objc2::ffi::objc_class;
+        let added = objc2::ffi::class_addMethod(
+            class,
+            sel!(applicationDockMenu:).as_ptr(),
+            Some(std::mem::transmute::<
+                extern "C" fn(&AnyObject, Sel, &AnyObject) -> *mut NSMenu,
+                unsafe extern "C" fn(),
+            >(dock_menu)),
+            c"@@:@".as_ptr(),
+        ) != objc2::ffi::NO
+            && objc2::ffi::class_addMethod(
+                class,
+                sel!(angmomDockItemSelected:).as_ptr(),
+                Some(std::mem::transmute::<
+                    extern "C" fn(&AnyObject, Sel, &NSMenuItem),
+                    unsafe extern "C" fn(),
+                >(dock_item_selected)),
+                c"v@:@".as_ptr(),
+            ) != objc2::ffi::NO;
+        if !added {
+            log::warn!("App delegate already handles the Dock menu; skipping");
+            return;
+        }
+    }
+
+    let menu = NSMenu::new(mtm);
+    for (tag, (_, title)) in ITEMS.iter().enumerate() {
+        unsafe {
+            let item = NSMenuItem::initWithTitle_action_keyEquivalent(
+                mtm.alloc(),
+                &NSString::from_str(title),
+                Some(sel!(angmomDockItemSelected:)),
+                &NSString::from_str(""),
+            );
+            item.setTarget(Some(delegate));
+            item.setTag(tag as isize);
+            menu.addItem(&item);
+        }
+    }
+    DOCK_MENU.with(|dock| *dock.borrow_mut() = Some(menu));
+}
@@ -0,0 +1,161 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{App, AppHandle, Emitter, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::error::{CommandError, ErrorCode};
+use crate::events;
+
+// Larger files are skipped rather than pushed through IPC in one piece.
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+// One entry in the `file://open` payload.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenedFile {
+    pub path: String,
+    pub contents: String,
+}
+
+// Files opened before the frontend is listening are queued here. Once it has
+// collected them with `take_opened_files` this becomes `None`, and later files
+// go straight out as `file://open`.
+pub struct OpenedFiles(Mutex<Option<Vec<OpenedFile>>>);
+
+impl Default for OpenedFiles {
+    fn default() -> Self {
+        Self(Mutex::new(Some(Vec::new())))
+    }
+}
+
+// Picks file paths out of a command line, skipping the executable, flags, and
+// URLs (deep links arrive on argv too on Windows and Linux).
+fn paths_from_args(args: &[String], cwd: &Path) -> Vec<PathBuf> {
+    args.iter()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-') && !arg.contains("://"))
+        .map(|arg| cwd.join(arg))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+fn read(path: &Path) -> Option<OpenedFile> {
+    let result = fs::metadata(path).and_then(|meta| {
+        if meta.len() > MAX_FILE_BYTES {
+            Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "file too large"))
+        } else {
+            fs::read_to_string(path)
+        }
+    });
+    match result {
+        Ok(contents) => Some(OpenedFile {
+            path: path.to_string_lossy().into_owned(),
+            contents,
+        }),
+        Err(e) => {
+            log::warn!("Failed to open {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+pub fn open_paths(app: &AppHandle, paths: &[PathBuf]) {
+    // Not managed when setup stopped early, e.g. for a silent update install.
+    let Some(state) = app.try_state::<OpenedFiles>() else { return };
+    let files: Vec<OpenedFile> = paths.iter().filter_map(|path| read(path)).collect();
+    if files.is_empty() {
+        return;
+    }
+    log::info!("Opening {} file(s)", files.len());
+
+    let mut queued = state.0.lock().unwrap();
+    match queued.as_mut() {
+        Some(queue) => queue.extend(files),
+        None => {
+            let _ = app.emit(events::FILE_OPEN, events::FilesOpened(files));
+        }
+    }
+}
+
+// Handles a command line, from this launch or one forwarded by a second instance.
+pub fn open_from_args(app: &AppHandle, args: &[String], cwd: &Path) {
+    open_paths(app, &paths_from_args(args, cwd));
+}
+
+pub fn init(app: &App) {
+    app.manage(OpenedFiles::default());
+
+    let args: Vec<String> = std::env::args().collect();
+    let cwd = std::env::current_dir().unwrap_or_default();
+    open_from_args(app.handle(), &args, &cwd);
+}
+
+// Returns the files the app was launched with and switches to event delivery.
+#[tauri::command]
+pub fn take_opened_files(opened: State<'_, OpenedFiles>) -> Vec<OpenedFile> {
+    opened.0.lock().unwrap().take().unwrap_or_default()
+}
+
+// One filter in the picker, e.g. `{ name: "Images", extensions: ["png", "jpg"] }`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileFilter {
+    pub name: String,
+    pub extensions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentEncoding {
+    Utf8,
+    Base64,
+}
+
+// Returned by `pick_and_read_file`. Text comes back as is; anything that isn't
+// valid UTF-8 is base64-encoded.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileContents {
+    pub path: String,
+    pub contents: String,
+    pub encoding: ContentEncoding,
+}
+
+// Asks the user for a file and reads it. `None` means the picker was cancelled.
+#[tauri::command]
+pub async fn pick_and_read_file(app: AppHandle, filters: Vec<FileFilter>) -> Result<Option<FileContents>, CommandError> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let mut dialog = app.dialog().file().set_title("Open File");
+    for filter in &filters {
+        let extensions: Vec<&str> = filter.extensions.iter().map(String::as_str).collect();
+        dialog = dialog.add_filter(&filter.name, &extensions);
+    }
+    dialog.pick_file(move |path| {
+        let _ = tx.send(path);
+    });
+    let Some(path) = rx.await.ok().flatten() else { return Ok(None) };
+    let path = path.into_path().map_err(|e| CommandError::new(ErrorCode::InvalidInput, e.to_string()))?;
+    let read_error = |e: std::io::Error| CommandError::io(&e, format!("Couldn't read {}: {}", path.display(), e));
+
+    let size = fs::metadata(&path).map_err(read_error)?.len();
+    if size > MAX_FILE_BYTES {
+        let message = format!(
+            "{} is {} MB; files larger than {} MB can't be opened.",
+            path.display(),
+            size / (1024 * 1024),
+            MAX_FILE_BYTES / (1024 * 1024)
+        );
+        return Err(CommandError::new(ErrorCode::TooLarge, message));
+    }
+    let bytes = fs::read(&path).map_err(read_error)?;
+    let (contents, encoding) = match String::from_utf8(bytes) {
+        Ok(text) => (text, ContentEncoding::Utf8),
+        Err(e) => (base64::engine::general_purpose::STANDARD.encode(e.into_bytes()), ContentEncoding::Base64),
+    };
+    log::info!("Read {} ({} bytes) from the file picker", path.display(), size);
+    Ok(Some(FileContents {
+        path: path.to_string_lossy().into_owned(),
+        contents,
+        encoding,
+    }))
+}
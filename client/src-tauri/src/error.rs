@@ -0,0 +1,81 @@
+use serde::Serialize;
+use std::fmt;
+
+// Stable, machine-readable reason a command failed. Serialized in
+// SCREAMING_SNAKE_CASE; codes are only ever added, never renamed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    PermissionDenied,
+    NotFound,
+    Offline,
+    Timeout,
+    Tls,
+    InvalidInput,
+    TooLarge,
+    DiskFull,
+    SignatureInvalid,
+    UnsupportedVersion,
+    UpToDate,
+    Unavailable,
+    Io,
+    Internal,
+    // Not possible on this platform.
+    Unsupported,
+}
+
+// What commands return to the frontend instead of a bare string, so it can
+// branch on `code` (and localize) while `message` stays readable English for
+// logs and as a fallback.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+
+    // An I/O failure described by `message`, coded by what kind of failure it was.
+    pub fn io(e: &std::io::Error, message: impl Into<String>) -> Self {
+        let code = match e.kind() {
+            std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+            std::io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+            std::io::ErrorKind::TimedOut => ErrorCode::Timeout,
+            _ => ErrorCode::Io,
+        };
+        Self::new(code, message)
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(e: std::io::Error) -> Self {
+        Self::io(&e, e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommandError, ErrorCode};
+    use serde_json::json;
+
+    #[test]
+    fn serializes_code_and_message() {
+        let error = CommandError::new(ErrorCode::PermissionDenied, "nope");
+        assert_eq!(serde_json::to_value(error).unwrap(), json!({ "code": "PERMISSION_DENIED", "message": "nope" }));
+    }
+
+    #[test]
+    fn io_errors_keep_their_kind() {
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        assert_eq!(CommandError::from(missing).code, ErrorCode::NotFound);
+    }
+}
@@ -0,0 +1,185 @@
+// Every event the backend emits, with its payload: the contract the frontend
+// listens against. Events without a payload are sent with `()` (`null` in JS).
+// Payloads that are a bare value on the wire wrap it in a transparent newtype.
+use serde::Serialize;
+#[cfg(desktop)]
+use tauri::Theme;
+
+use crate::feature_flags::FeatureFlags;
+#[cfg(desktop)]
+use crate::settings::ThemePreference;
+
+pub const DEEP_LINK_NAVIGATE: &str = "deep-link://navigate";
+pub const FEATURE_FLAGS_CHANGED: &str = "feature-flags://changed";
+pub const GREET_PROGRESS: &str = "greet://progress";
+// Sent by the frontend to stop a running `greet_async`; the sync `greet` can't be cancelled.
+pub const GREET_CANCEL: &str = "greet://cancel";
+pub const NETWORK_CHANGED: &str = "network://changed";
+pub const NOTIFICATION_ACTIONED: &str = "notification://actioned";
+
+#[cfg(desktop)]
+pub const APP_RESTARTING: &str = "app://restarting";
+#[cfg(desktop)]
+pub const FILE_OPEN: &str = "file://open";
+#[cfg(desktop)]
+pub const THEME_CHANGED: &str = "theme://changed";
+#[cfg(desktop)]
+pub const SYSTEM_THEME_CHANGED: &str = "system://theme-changed";
+
+#[cfg(desktop)]
+pub const UPDATE_DOWNLOAD_PROGRESS: &str = "update://download-progress";
+#[cfg(desktop)]
+pub const UPDATE_PATCH_PROGRESS: &str = "update://patch-progress";
+#[cfg(desktop)]
+pub const UPDATE_PATCHING: &str = "update://patching";
+#[cfg(desktop)]
+pub const UPDATE_PATCH_FAILED: &str = "update://patch-failed";
+#[cfg(desktop)]
+pub const UPDATE_PAUSED: &str = "update://paused";
+#[cfg(desktop)]
+pub const UPDATE_RESUMED: &str = "update://resumed";
+#[cfg(desktop)]
+pub const UPDATE_CANCELLED: &str = "update://cancelled";
+#[cfg(desktop)]
+pub const UPDATE_DOWNLOAD_FINISHED: &str = "update://download-finished";
+
+#[cfg(desktop)]
+pub const WINDOW_FULLSCREEN_CHANGED: &str = "window://fullscreen-changed";
+#[cfg(desktop)]
+pub const WINDOW_MAXIMIZED_CHANGED: &str = "window://maximized-changed";
+#[cfg(desktop)]
+pub const WINDOW_ZOOM_CHANGED: &str = "window://zoom-changed";
+
+// `deep-link://navigate`; also what `take_pending_deep_link` returns.
+#[derive(Debug, Clone, Serialize)]
+pub struct NavigateRequest {
+    pub url: String,
+    pub route: String,
+}
+
+// `feature-flags://changed`: the effective flags after the change.
+#[derive(Clone, Serialize)]
+#[serde(transparent)]
+pub struct FeatureFlagsChanged(pub FeatureFlags);
+
+// `greet://progress`, sent to the calling window only.
+#[derive(Clone, Serialize)]
+pub struct GreetProgress {
+    pub step: u32,
+    pub total: u32,
+}
+
+// `network://changed`: whether the network is now reachable.
+#[derive(Clone, Serialize)]
+#[serde(transparent)]
+pub struct NetworkChanged(pub bool);
+
+// `notification://actioned`.
+#[derive(Clone, Serialize)]
+pub struct NotificationActioned {
+    pub id: i32,
+    pub action: Option<String>,
+}
+
+// `theme://changed`. `theme` is the appearance actually in effect, which for
+// `System` follows the OS.
+#[cfg(desktop)]
+#[derive(Clone, Serialize)]
+pub struct ThemeChanged {
+    pub preference: ThemePreference,
+    pub theme: Option<Theme>,
+}
+
+// `system://theme-changed`: just the new OS appearance ("light"/"dark").
+#[cfg(desktop)]
+#[derive(Clone, Serialize)]
+#[serde(transparent)]
+pub struct SystemThemeChanged(pub Theme);
+
+// `file://open`.
+#[cfg(desktop)]
+#[derive(Clone, Serialize)]
+#[serde(transparent)]
+pub struct FilesOpened(pub Vec<crate::documents::OpenedFile>);
+
+// `update://download-progress` and `update://patch-progress`. `total` is `None`
+// when the server doesn't advertise a content length, in which case
+// `indeterminate` is set.
+#[cfg(desktop)]
+#[derive(Clone, Serialize)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub indeterminate: bool,
+}
+
+// `update://paused` and `update://resumed`: bytes downloaded so far.
+#[cfg(desktop)]
+#[derive(Clone, Serialize)]
+#[serde(transparent)]
+pub struct DownloadOffset(pub u64);
+
+// `update://patch-failed`: why, worded for users; the full download follows.
+#[cfg(desktop)]
+#[derive(Clone, Serialize)]
+#[serde(transparent)]
+pub struct PatchFailed(pub String);
+
+// `window://fullscreen-changed` and `window://maximized-changed`, sent to the
+// window concerned.
+#[cfg(desktop)]
+#[derive(Clone, Serialize)]
+#[serde(transparent)]
+pub struct WindowFlagChanged(pub bool);
+
+// `window://zoom-changed`, sent to every window since the level is shared.
+#[cfg(desktop)]
+#[derive(Clone, Serialize)]
+#[serde(transparent)]
+pub struct ZoomChanged(pub f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feature_flags::FlagValue;
+    use serde_json::{json, to_value};
+
+    #[test]
+    fn cross_platform_payloads() {
+        assert_eq!(
+            to_value(NavigateRequest { url: "angmom://settings".into(), route: "/settings".into() }).unwrap(),
+            json!({ "url": "angmom://settings", "route": "/settings" })
+        );
+        let flags = FeatureFlags::from([("welcome_banner".to_string(), FlagValue::Bool(true))]);
+        assert_eq!(to_value(FeatureFlagsChanged(flags)).unwrap(), json!({ "welcome_banner": true }));
+        assert_eq!(to_value(GreetProgress { step: 1, total: 3 }).unwrap(), json!({ "step": 1, "total": 3 }));
+        assert_eq!(to_value(NetworkChanged(false)).unwrap(), json!(false));
+        assert_eq!(
+            to_value(NotificationActioned { id: 7, action: None }).unwrap(),
+            json!({ "id": 7, "action": null })
+        );
+    }
+
+    #[cfg(desktop)]
+    #[test]
+    fn desktop_payloads() {
+        let file = crate::documents::OpenedFile { path: "/tmp/a.txt".into(), contents: "hi".into() };
+        assert_eq!(
+            to_value(FilesOpened(vec![file])).unwrap(),
+            json!([{ "path": "/tmp/a.txt", "contents": "hi" }])
+        );
+        assert_eq!(
+            to_value(DownloadProgress { downloaded: 10, total: None, indeterminate: true }).unwrap(),
+            json!({ "downloaded": 10, "total": null, "indeterminate": true })
+        );
+        assert_eq!(
+            to_value(ThemeChanged { preference: ThemePreference::System, theme: Some(Theme::Dark) }).unwrap(),
+            json!({ "preference": "system", "theme": "dark" })
+        );
+        assert_eq!(to_value(SystemThemeChanged(Theme::Light)).unwrap(), json!("light"));
+        assert_eq!(to_value(DownloadOffset(512)).unwrap(), json!(512));
+        assert_eq!(to_value(PatchFailed("no base".into())).unwrap(), json!("no base"));
+        assert_eq!(to_value(WindowFlagChanged(true)).unwrap(), json!(true));
+        assert_eq!(to_value(ZoomChanged(1.25)).unwrap(), json!(1.25));
+    }
+}
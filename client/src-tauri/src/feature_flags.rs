@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{App, AppHandle, Emitter, Manager, State};
+
+use crate::events;
+
+// Shipped defaults; an older build simply never sees flags added after it.
+const DEFAULTS: &str = include_str!("../feature-flags.json");
+// JSON object of flag overrides for testing, e.g. `ANGMOM_FEATURE_FLAGS='{"welcome_banner":false}'`.
+const FEATURE_FLAGS_ENV: &str = "ANGMOM_FEATURE_FLAGS";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FlagValue {
+    Bool(bool),
+    String(String),
+}
+
+pub type FeatureFlags = HashMap<String, FlagValue>;
+
+// Each source of flags, applied in increasing precedence: bundled defaults, the
+// update manifest's `feature_flags`, the env var, then runtime overrides (dev only).
+#[derive(Default)]
+struct Layers {
+    defaults: FeatureFlags,
+    manifest: FeatureFlags,
+    env: FeatureFlags,
+    runtime: FeatureFlags,
+}
+
+impl Layers {
+    fn effective(&self) -> FeatureFlags {
+        let mut flags = self.defaults.clone();
+        for layer in [&self.manifest, &self.env, &self.runtime] {
+            flags.extend(layer.iter().map(|(name, value)| (name.clone(), value.clone())));
+        }
+        flags
+    }
+}
+
+pub struct FeatureFlagState(Mutex<Layers>);
+
+// Values that aren't a bool or string are dropped rather than failing the whole set.
+fn parse_flags(value: &serde_json::Value) -> FeatureFlags {
+    let Some(object) = value.as_object() else { return FeatureFlags::new() };
+    object
+        .iter()
+        .filter_map(|(name, value)| match serde_json::from_value(value.clone()) {
+            Ok(flag) => Some((name.clone(), flag)),
+            Err(_) => {
+                log::warn!("Ignoring feature flag {} with unsupported value {}", name, value);
+                None
+            }
+        })
+        .collect()
+}
+
+fn parse_json_flags(source: &str, json: &str) -> FeatureFlags {
+    match serde_json::from_str(json) {
+        Ok(value) => parse_flags(&value),
+        Err(e) => {
+            log::warn!("Ignoring malformed feature flags from {}: {}", source, e);
+            FeatureFlags::new()
+        }
+    }
+}
+
+pub fn init(app: &App) {
+    let layers = Layers {
+        defaults: parse_json_flags("bundled defaults", DEFAULTS),
+        env: std::env::var(FEATURE_FLAGS_ENV)
+            .map(|json| parse_json_flags(FEATURE_FLAGS_ENV, &json))
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+    app.manage(FeatureFlagState(Mutex::new(layers)));
+}
+
+fn changed(app: &AppHandle, layers: &Layers) {
+    let _ = app.emit(events::FEATURE_FLAGS_CHANGED, events::FeatureFlagsChanged(layers.effective()));
+}
+
+// Applies the `feature_flags` object from an update manifest, replacing any
+// earlier one. Like `recheck_hours`, only seen when the manifest has an update.
+#[cfg(desktop)]
+pub fn apply_manifest(app: &AppHandle, raw_json: &serde_json::Value) {
+    let Some(flags) = raw_json.get("feature_flags") else { return };
+    let mut layers = app.state::<FeatureFlagState>().0.lock().unwrap();
+    layers.manifest = parse_flags(flags);
+    changed(app, &layers);
+}
+
+#[tauri::command]
+pub fn feature_flags(state: State<'_, FeatureFlagState>) -> FeatureFlags {
+    state.0.lock().unwrap().effective()
+}
+
+// Overrides a flag until the app quits, for testing dark-launched features.
+// `None` removes the override.
+#[cfg(debug_assertions)]
+#[tauri::command]
+pub fn set_feature_flag(app: AppHandle, name: String, value: Option<FlagValue>) -> FeatureFlags {
+    let mut layers = app.state::<FeatureFlagState>().0.lock().unwrap();
+    match value {
+        Some(value) => layers.runtime.insert(name, value),
+        None => layers.runtime.remove(&name),
+    };
+    changed(&app, &layers);
+    layers.effective()
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Menu strings, keyed the same in every language. Adding a language means adding
+// a table here and an entry in `LANGUAGES`; keys it leaves out stay in English.
+const EN: &[(&str, &str)] = &[
+    ("about", "About Angular Momentum"),
+    ("check_updates", "Check for Updates..."),
+    ("preferences", "Preferences..."),
+    ("launch_at_login", "Launch at Login"),
+    ("clear_cache_restart", "Clear Cache and Restart..."),
+    ("hide", "Hide Angular Momentum"),
+    ("hide_others", "Hide Others"),
+    ("show_all", "Show All"),
+    ("quit", "Quit Angular Momentum"),
+    ("edit", "Edit"),
+    ("undo", "Undo"),
+    ("redo", "Redo"),
+    ("cut", "Cut"),
+    ("copy", "Copy"),
+    ("paste", "Paste"),
+    ("select_all", "Select All"),
+    ("window", "Window"),
+    ("minimize", "Minimize"),
+    ("full_screen", "Full Screen"),
+    ("keep_on_top", "Keep on Top"),
+    ("reset_window", "Reset Window Position"),
+    ("close_window", "Close Window"),
+    ("bring_all_to_front", "Bring All to Front"),
+];
+
+const ES: &[(&str, &str)] = &[
+    ("about", "Acerca de Angular Momentum"),
+    ("check_updates", "Buscar actualizaciones..."),
+    ("preferences", "Preferencias..."),
+    ("launch_at_login", "Abrir al iniciar sesión"),
+    ("clear_cache_restart", "Borrar caché y reiniciar..."),
+    ("hide", "Ocultar Angular Momentum"),
+    ("hide_others", "Ocultar otros"),
+    ("show_all", "Mostrar todo"),
+    ("quit", "Salir de Angular Momentum"),
+    ("edit", "Edición"),
+    ("undo", "Deshacer"),
+    ("redo", "Rehacer"),
+    ("cut", "Cortar"),
+    ("copy", "Copiar"),
+    ("paste", "Pegar"),
+    ("select_all", "Seleccionar todo"),
+    ("window", "Ventana"),
+    ("minimize", "Minimizar"),
+    ("full_screen", "Pantalla completa"),
+    ("keep_on_top", "Mantener encima"),
+    ("reset_window", "Restablecer posición de la ventana"),
+    ("close_window", "Cerrar ventana"),
+    ("bring_all_to_front", "Traer todo al frente"),
+];
+
+const FR: &[(&str, &str)] = &[
+    ("about", "À propos d'Angular Momentum"),
+    ("check_updates", "Rechercher des mises à jour..."),
+    ("preferences", "Préférences..."),
+    ("launch_at_login", "Ouvrir à la connexion"),
+    ("clear_cache_restart", "Vider le cache et redémarrer..."),
+    ("hide", "Masquer Angular Momentum"),
+    ("hide_others", "Masquer les autres"),
+    ("show_all", "Tout afficher"),
+    ("quit", "Quitter Angular Momentum"),
+    ("edit", "Édition"),
+    ("undo", "Annuler"),
+    ("redo", "Rétablir"),
+    ("cut", "Couper"),
+    ("copy", "Copier"),
+    ("paste", "Coller"),
+    ("select_all", "Tout sélectionner"),
+    ("window", "Fenêtre"),
+    ("minimize", "Réduire"),
+    ("full_screen", "Plein écran"),
+    ("keep_on_top", "Garder au premier plan"),
+    ("reset_window", "Réinitialiser la position de la fenêtre"),
+    ("close_window", "Fermer la fenêtre"),
+    ("bring_all_to_front", "Tout ramener au premier plan"),
+];
+
+const DE: &[(&str, &str)] = &[
+    ("about", "Über Angular Momentum"),
+    ("check_updates", "Nach Updates suchen..."),
+    ("preferences", "Einstellungen..."),
+    ("launch_at_login", "Bei Anmeldung öffnen"),
+    ("clear_cache_restart", "Cache leeren und neu starten..."),
+    ("hide", "Angular Momentum ausblenden"),
+    ("hide_others", "Andere ausblenden"),
+    ("show_all", "Alle einblenden"),
+    ("quit", "Angular Momentum beenden"),
+    ("edit", "Bearbeiten"),
+    ("undo", "Widerrufen"),
+    ("redo", "Wiederholen"),
+    ("cut", "Ausschneiden"),
+    ("copy", "Kopieren"),
+    ("paste", "Einsetzen"),
+    ("select_all", "Alles auswählen"),
+    ("window", "Fenster"),
+    ("minimize", "Minimieren"),
+    ("full_screen", "Vollbild"),
+    ("keep_on_top", "Im Vordergrund halten"),
+    ("reset_window", "Fensterposition zurücksetzen"),
+    ("close_window", "Fenster schließen"),
+    ("bring_all_to_front", "Alle nach vorne bringen"),
+];
+
+// By ISO 639-1 language code.
+const LANGUAGES: &[(&str, &[(&str, &str)])] = &[("es", ES), ("fr", FR), ("de", DE)];
+
+static STRINGS: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+
+// English, overlaid with the system language's table if there is one.
+fn load() -> HashMap<&'static str, &'static str> {
+    let mut strings: HashMap<_, _> = EN.iter().copied().collect();
+    let locale = sys_locale::get_locale().unwrap_or_default();
+    let language = locale.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+    match LANGUAGES.iter().find(|(code, _)| *code == language) {
+        Some((code, table)) => {
+            strings.extend(table.iter().copied());
+            log::debug!("Menu strings in {} for locale {}", code, locale);
+        }
+        None => log::debug!("No menu strings for locale {:?}; using English", locale),
+    }
+    strings
+}
+
+// The menu string for `key`, or the key itself if no table has it.
+pub fn t(key: &'static str) -> &'static str {
+    STRINGS.get_or_init(load).get(key).copied().unwrap_or(key)
+}
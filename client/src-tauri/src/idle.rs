@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+// Seconds since the last keyboard or mouse input anywhere on the system, or `None`
+// where that can't be read. Linux has no portable API for it (X11 needs
+// XScreenSaver, and Wayland doesn't expose it at all), so there it's unknown.
+#[cfg(target_os = "macos")]
+pub fn system_idle_seconds() -> Option<u64> {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+    }
+    // kCGEventSourceStateCombinedSessionState and kCGAnyInputEventType.
+    const COMBINED_SESSION_STATE: i32 = 0;
+    const ANY_INPUT_EVENT: u32 = u32::MAX;
+    // SAFETY: a plain query; no pointers are involved.
+    let seconds = unsafe { CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT) };
+    (seconds.is_finite() && seconds >= 0.0).then_some(seconds as u64)
+}
+
+#[cfg(target_os = "windows")]
+pub fn system_idle_seconds() -> Option<u64> {
+    use windows_sys::Win32::System::SystemInformation::GetTickCount;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO { cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32, dwTime: 0 };
+    // SAFETY: `info` is a correctly sized LASTINPUTINFO that outlives the call.
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return None;
+    }
+    // Both are milliseconds since boot and wrap together after ~49 days.
+    // SAFETY: no arguments.
+    let now = unsafe { GetTickCount() };
+    Some(u64::from(now.wrapping_sub(info.dwTime)) / 1000)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn system_idle_seconds() -> Option<u64> {
+    None
+}
+
+// Whether the machine has gone at least `threshold` without input. Unknown idle
+// time counts as idle, so deferring never turns into never installing.
+pub fn idle_for(threshold: Duration) -> bool {
+    !matches!(system_idle_seconds(), Some(seconds) if seconds < threshold.as_secs())
+}
+
+// For testing idle-gated behavior.
+#[cfg(debug_assertions)]
+#[tauri::command]
+pub fn idle_seconds() -> Option<u64> {
+    system_idle_seconds()
+}
@@ -0,0 +1,101 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+const STORE_FILE: &str = "kv-store.json";
+// Keeps the store small enough to rewrite on every change.
+const MAX_KEY_LEN: usize = 256;
+
+// Small frontend state that should outlive localStorage. Lives in the app data dir
+// and is rewritten whole on each change while the lock is held, so concurrent
+// writes land one after another and the file always matches the map.
+pub struct KvStore {
+    path: Option<PathBuf>,
+    entries: Mutex<BTreeMap<String, Value>>,
+}
+
+impl KvStore {
+    fn load(app: &AppHandle) -> Self {
+        let path = app.path().app_data_dir().ok().map(|dir| dir.join(STORE_FILE));
+        let entries = match path.as_ref().map(fs::read_to_string) {
+            Some(Ok(contents)) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                log::warn!("Key-value store is unreadable, starting empty: {}", e);
+                // Set it aside rather than overwriting it on the next write.
+                if let Some(path) = &path {
+                    let _ = fs::rename(path, path.with_extension("corrupt.json"));
+                }
+                BTreeMap::new()
+            }),
+            _ => BTreeMap::new(),
+        };
+        Self { path, entries: Mutex::new(entries) }
+    }
+
+    // Writes to a temp file and renames it over the store, so a crash mid-write
+    // leaves the previous contents intact.
+    fn flush(&self, entries: &BTreeMap<String, Value>) -> Result<(), String> {
+        let path = self.path.as_ref().ok_or("App data dir unavailable")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string(entries).map_err(|e| e.to_string())?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, json).map_err(|e| e.to_string())?;
+        fs::rename(&temp, path).map_err(|e| e.to_string())
+    }
+
+    // Applies `change` and flushes, rolling the map back if the write fails so
+    // memory never claims something the disk doesn't have.
+    fn update(&self, change: impl FnOnce(&mut BTreeMap<String, Value>)) -> Result<(), String> {
+        let mut entries = self.entries.lock().unwrap();
+        let previous = entries.clone();
+        change(&mut entries);
+        self.flush(&entries).inspect_err(|_| *entries = previous)
+    }
+}
+
+pub fn init(app: &AppHandle) {
+    app.manage(KvStore::load(app));
+}
+
+// Every change is written through (or rolled back) under the lock, so the file
+// only lags memory while a write is in flight; this returns once that has landed.
+pub fn wait_for_writes(app: &AppHandle) {
+    if let Some(store) = app.try_state::<KvStore>() {
+        drop(store.entries.lock().unwrap());
+    }
+}
+
+fn check_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return Err(format!("Keys must be 1–{} bytes long", MAX_KEY_LEN));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn kv_get(store: State<KvStore>, key: String) -> Option<Value> {
+    store.entries.lock().unwrap().get(&key).cloned()
+}
+
+#[tauri::command]
+pub fn kv_set(store: State<KvStore>, key: String, value: Value) -> Result<(), String> {
+    check_key(&key)?;
+    store.update(|entries| {
+        entries.insert(key, value);
+    })
+}
+
+// Deleting a key that isn't there is not an error.
+#[tauri::command]
+pub fn kv_delete(store: State<KvStore>, key: String) -> Result<(), String> {
+    if !store.entries.lock().unwrap().contains_key(&key) {
+        return Ok(());
+    }
+    store.update(|entries| {
+        entries.remove(&key);
+    })
+}
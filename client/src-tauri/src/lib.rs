@@ -1,153 +1,598 @@
 #[cfg(desktop)]
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+mod about;
+mod audit;
 #[cfg(desktop)]
-use tauri_plugin_updater::UpdaterExt;
+mod autostart;
+#[cfg(desktop)]
+mod badge;
+mod cache;
+#[cfg(desktop)]
+mod capture;
+mod changelog;
+mod crash;
+mod deep_link;
+mod diagnostics;
+#[cfg(target_os = "macos")]
+mod dock;
+#[cfg(desktop)]
+mod documents;
+mod error;
+mod events;
+mod feature_flags;
+#[cfg(desktop)]
+mod feedback;
+#[cfg(desktop)]
+mod i18n;
+#[cfg(desktop)]
+mod idle;
+mod kv;
+mod lifecycle;
+mod links;
+mod logging;
+#[cfg(desktop)]
+mod menu;
+mod network;
+mod notifications;
+#[cfg(desktop)]
+mod progress;
+#[cfg(desktop)]
+mod rollback;
+mod schedule;
+mod settings;
+#[cfg(desktop)]
+mod shortcuts;
+mod startup;
+#[cfg(desktop)]
+mod theme;
+#[cfg(desktop)]
+mod tray;
+mod unsaved;
+#[cfg(desktop)]
+mod updater;
+#[cfg(desktop)]
+mod user_agent;
+#[cfg(desktop)]
+mod window_state;
+#[cfg(desktop)]
+mod windows;
+#[cfg(desktop)]
+mod zoom;
+
 #[cfg(desktop)]
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri::{Emitter, Listener, Manager};
+use tauri_plugin_opener::OpenerExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(desktop)]
+const PROJECT_URL: &str = "https://github.com/TheGameKnave/angular-momentum";
+#[cfg(desktop)]
+const COPYRIGHT: &str = "Copyright © 2024 GameKnave Design, LLC";
+// Help menu links. New issues get the version and platform appended as query params.
+#[cfg(desktop)]
+const ISSUES_URL: &str = "https://github.com/TheGameKnave/angular-momentum/issues/new";
 #[cfg(desktop)]
-use tauri::Manager;
+const DOCS_URL: &str = "https://github.com/TheGameKnave/angular-momentum#readme";
+// Most of the diagnostics report put in a new-issue link; browsers and GitHub
+// reject overly long URLs, and "Copy Diagnostics" has the full report.
+#[cfg(desktop)]
+const ISSUE_REPORT_MAX_CHARS: usize = 1000;
 
+/// Deprecated: kept for existing callers; new code should use `greet_async`.
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+// Template for long-running commands: validates input and returns errors as
+// `Err(String)`, reports progress to the calling window, and stops early when
+// that window emits `greet://cancel`.
+#[tauri::command]
+async fn greet_async(name: String, window: tauri::Window) -> Result<String, String> {
+    const STEPS: u32 = 3;
+
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Name must not be empty".into());
+    }
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let flag = cancelled.clone();
+    let listener = window.once(events::GREET_CANCEL, move |_| flag.store(true, Ordering::Relaxed));
+
+    for step in 1..=STEPS {
+        if cancelled.load(Ordering::Relaxed) {
+            window.unlisten(listener);
+            return Err("Cancelled".into());
+        }
+        // Stand-in for real work.
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        let _ = window.emit_to(window.label(), events::GREET_PROGRESS, events::GreetProgress { step, total: STEPS });
+    }
+
+    window.unlisten(listener);
+    Ok(format!("Hello, {}! You've been greeted asynchronously from Rust!", name))
+}
+
+// Running app version, suffixed with the commit hash when GIT_HASH is set at build time.
+#[tauri::command]
+fn app_version(app: tauri::AppHandle) -> String {
+    let version = app.package_info().version.to_string();
+    match option_env!("GIT_HASH") {
+        Some(hash) if !hash.is_empty() => format!("{} ({})", version, hash),
+        _ => version,
+    }
+}
+
+// Opens the app config dir (where settings.json lives) in the system file manager.
+#[tauri::command]
+fn open_config_dir(app: tauri::AppHandle) -> Result<(), String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+// New-issue link pre-filled with the running build, so reports arrive with context.
+#[cfg(desktop)]
+fn report_issue_url(app: &tauri::AppHandle) -> String {
+    let info = diagnostics::collect(app);
+    let report = info.to_report();
+    let report = match report.char_indices().nth(ISSUE_REPORT_MAX_CHARS) {
+        Some((end, _)) => format!("{}…", &report[..end]),
+        None => report,
+    };
+    let mut url = tauri::Url::parse(ISSUES_URL).expect("ISSUES_URL is a valid URL");
+    url.query_pairs_mut()
+        .append_pair("version", &info.app_version)
+        .append_pair("platform", &format!("{} {} ({})", info.os, info.os_version, info.arch))
+        .append_pair("body", &format!("\n\n---\n{}", report));
+    url.into()
+}
+
+#[cfg(desktop)]
+fn open_link(app: &tauri::AppHandle, url: &str) {
+    if let Err(e) = app.opener().open_url(url, None::<&str>) {
+        log::error!("Failed to open {}: {}", url, e);
+    }
+}
+
+// Shared by the app menu, the tray menu and, on macOS, the Dock menu.
+#[cfg(desktop)]
+fn handle_menu_event(app: &tauri::AppHandle, id: &str) {
+    if let Some(preference) = theme::from_menu_id(id) {
+        theme::set(app, preference);
+    } else if let Some(label) = id.strip_prefix(menu::WINDOW_ITEM_PREFIX) {
+        if let Err(e) = windows::focus(app, label) {
+            log::warn!("Failed to focus window: {}", e);
+        }
+    } else if matches!(id, "check_updates" | "help_check_updates" | "tray_check_updates") {
+        tauri::async_runtime::spawn(updater::check_for_updates(app.clone(), true));
+    } else if id == "tray_show" {
+        tray::show_main_window(app);
+    } else if id == "tray_quit" {
+        app.exit(0);
+    } else if id == "about" {
+        #[cfg(not(target_os = "macos"))]
+        about::show_dialog(app);
+    } else if id == autostart::MENU_ID {
+        let enabled = !settings::current(app).launch_at_login;
+        if let Err(e) = autostart::set(app, enabled) {
+            log::error!("Failed to change launch at login: {}", e);
+        }
+    } else if id == "new_window" {
+        if let Err(e) = windows::open_new_window(app) {
+            log::error!("Failed to open new window: {}", e);
+        }
+    } else if id == windows::WHATS_NEW_MENU_ID {
+        if let Err(e) = windows::open_whats_new(app) {
+            log::error!("Failed to open What's New window: {}", e);
+        }
+    } else if id == windows::BACK_TO_APP_MENU_ID {
+        windows::back_to_app(app);
+    } else if id == "preferences" {
+        if let Err(e) = windows::open_preferences(app) {
+            log::error!("Failed to open preferences window: {}", e);
+        }
+    } else if matches!(id, "zoom_in" | "zoom_out" | "zoom_reset") {
+        let steps = match id {
+            "zoom_in" => 1,
+            "zoom_out" => -1,
+            _ => 0,
+        };
+        if let Some(window) = windows::target_window(app) {
+            zoom::step(&window, steps);
+        }
+    } else if matches!(id, "reload" | "force_reload") {
+        if let Some(window) = windows::target_window(app) {
+            if let Err(e) = windows::reload(&window, id == "force_reload") {
+                log::error!("Failed to reload window: {}", e);
+            }
+        }
+    } else if id == "toggle_devtools" {
+        #[cfg(any(debug_assertions, feature = "devtools"))]
+        windows::toggle_target_devtools(app);
+    } else if id == "bring_all_to_front" {
+        #[cfg(target_os = "macos")]
+        windows::bring_all_to_front(app);
+    } else if id == windows::FULLSCREEN_MENU_ID {
+        if let Some(window) = windows::target_window(app) {
+            if let Err(e) = windows::toggle_fullscreen_window(&window.as_ref().window()) {
+                log::error!("Failed to toggle fullscreen: {}", e);
+            }
+        }
+    } else if id == windows::KEEP_ON_TOP_MENU_ID {
+        // Applies to the main window, the one whose choice is remembered.
+        if let Some(window) = app.get_webview_window("main") {
+            let enabled = !settings::current(app).always_on_top;
+            if let Err(e) = windows::set_keep_on_top(&window.as_ref().window(), enabled) {
+                log::error!("Failed to change keep-on-top: {}", e);
+            }
+        }
+    } else if id == "reset_window" {
+        if let Some(window) = app.get_webview_window("main") {
+            window_state::reset(&window);
+        }
+    } else if id == "reveal_config" {
+        if let Err(e) = open_config_dir(app.clone()) {
+            app.dialog()
+                .message(format!("Failed to open config folder: {}", e))
+                .kind(MessageDialogKind::Error)
+                .title("Reveal Config Folder")
+                .show(|_| {});
+        }
+    } else if id == "report_issue" {
+        open_link(app, &report_issue_url(app));
+    } else if id == "documentation" {
+        open_link(app, DOCS_URL);
+    } else if id == "send_feedback" {
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            if let Err(e) = feedback::prepare_bundle(&app_handle) {
+                app_handle.dialog()
+                    .message(e)
+                    .kind(MessageDialogKind::Error)
+                    .title("Send Feedback")
+                    .show(|_| {});
+            }
+        });
+    } else if id == "copy_diagnostics" {
+        if let Err(e) = diagnostics::copy_diagnostics(app.clone()) {
+            log::error!("Failed to copy diagnostics: {}", e);
+        }
+    } else if id == "test_notification" {
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = notifications::send_test(&app_handle) {
+                app_handle.dialog()
+                    .message(e)
+                    .kind(MessageDialogKind::Warning)
+                    .title("Send Test Notification")
+                    .show(|_| {});
+            }
+        });
+    } else if id == "clear_app_cache" {
+        let app_handle = app.clone();
+        app.dialog()
+            .message("Delete cached files, such as downloaded updates? Your settings and data are kept.")
+            .title("Clear Cached Files")
+            .buttons(MessageDialogButtons::OkCancel)
+            .show(move |confirmed| {
+                if !confirmed {
+                    return;
+                }
+                let (message, kind) = match cache::clear(&app_handle) {
+                    Ok(freed) => (
+                        format!("Freed {:.1} MB.", freed as f64 / (1024.0 * 1024.0)),
+                        MessageDialogKind::Info,
+                    ),
+                    Err(e) => (format!("Failed to clear the cache: {}", e), MessageDialogKind::Error),
+                };
+                app_handle.dialog().message(message).kind(kind).title("Clear Cached Files").show(|_| {});
+            });
+    } else if id == "clear_cache" {
+        let app_handle = app.clone();
+        let confirmed = app_handle.dialog()
+            .message("This will clear the app's web data, including cookies and local storage, and restart the app. You may need to log in again. Continue?")
+            .title("Clear Cache and Restart")
+            .buttons(MessageDialogButtons::OkCancel)
+            .blocking_show();
+
+        if confirmed {
+            unsaved::discard(&app_handle);
+            // Clear WebView data
+            if let Some(window) = app_handle.get_webview_window("main") {
+                let _ = window.clear_all_browsing_data();
+            }
+            // Restart the app
+            app_handle.restart();
+        }
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let builder = tauri::Builder::default()
+    crash::install_panic_hook();
+    let builder = tauri::Builder::default();
+
+    // Must be registered first. A second launch focuses this instance instead; deep
+    // links it was given are re-dispatched here through the deep-link plugin.
+    #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+        tray::show_main_window(app);
+        documents::open_from_args(app, &argv, std::path::Path::new(&cwd));
+    }));
+
+    let builder = builder
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_notification::init())
-        .plugin(tauri_plugin_deep_link::init());
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_os::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .setup(|app| {
+            let (settings, status) = settings::load(app.handle());
+            logging::init(app, &settings)?;
+            crash::init(app.handle());
+            #[cfg(desktop)]
+            let first_run = matches!(status, settings::LoadStatus::Missing);
+            match status {
+                settings::LoadStatus::Loaded => {}
+                settings::LoadStatus::Missing => log::info!("No settings file found; using defaults"),
+                settings::LoadStatus::Corrupt(e) => log::warn!("Settings file unreadable, using defaults: {}", e),
+            }
+            app.manage(Mutex::new(settings));
+            app.manage(unsaved::UnsavedChanges::default());
+            app.manage(audit::LastCalls::default());
+            kv::init(app.handle());
+            network::init(app);
+            feature_flags::init(app);
+
+            #[cfg(desktop)]
+            updater::init(app);
+            // Unattended install: no menu, tray, shortcuts, prompts or window at all.
+            #[cfg(desktop)]
+            if updater::is_silent_update_requested() {
+                tauri::async_runtime::spawn(updater::run_silent_update(app.handle().clone()));
+                return Ok(());
+            }
+            startup::create_main_window(app)?;
+
+            notifications::request_on_launch(app.handle().clone());
+            schedule::init(app);
+            startup::spawn_ready_timeout(app.handle().clone());
+
+            #[cfg(desktop)]
+            {
+                let rollback_to = rollback::record_launch(app.handle());
+                documents::init(app);
+                app.manage(windows::WindowFlags::default());
+                theme::restore(app.handle());
+                autostart::restore(app.handle());
+                menu::create(app);
+                menu::refresh_window_list(app.handle(), None);
+                #[cfg(target_os = "macos")]
+                dock::create(app.handle());
+                tray::create(app)?;
+                shortcuts::register(app)?;
+                updater::spawn_background_checks(app.handle().clone());
+                if first_run && updater::is_available(app.handle()) {
+                    updater::prompt_first_run(app.handle());
+                }
+
+                if let Some(window) = app.get_webview_window("main") {
+                    window_state::restore(&window);
+                    zoom::restore(&window);
+                    windows::restore_keep_on_top(&window);
+                    windows::restore_menu_visibility(&window.as_ref().window());
+                    windows::restore_decorations(&window.as_ref().window());
+                    windows::restore_opacity(&window.as_ref().window());
+                }
+                windows::show_whats_new_after_update(app.handle());
+
+                if let Some(previous) = rollback_to {
+                    rollback::offer_rollback(app.handle(), previous);
+                }
+            }
+            deep_link::init(app);
+            Ok(())
+        });
 
     #[cfg(desktop)]
     let builder = builder
-        .plugin(tauri_plugin_updater::Builder::new().build())
-        .setup(|app| {
-            // App menu (Angular Momentum)
-            let check_updates = MenuItem::with_id(app, "check_updates", "Check for Updates...", true, None::<&str>)?;
-            let clear_cache = MenuItem::with_id(app, "clear_cache", "Clear Cache and Restart...", true, None::<&str>)?;
-            let separator = PredefinedMenuItem::separator(app)?;
-            let hide = PredefinedMenuItem::hide(app, Some("Hide Angular Momentum"))?;
-            let hide_others = PredefinedMenuItem::hide_others(app, Some("Hide Others"))?;
-            let show_all = PredefinedMenuItem::show_all(app, Some("Show All"))?;
-            let separator2 = PredefinedMenuItem::separator(app)?;
-            let quit = PredefinedMenuItem::quit(app, Some("Quit Angular Momentum"))?;
-            let app_submenu = Submenu::with_items(
-                app,
-                "Angular Momentum",
-                true,
-                &[&check_updates, &clear_cache, &separator, &hide, &hide_others, &show_all, &separator2, &quit],
-            )?;
-
-            // Edit menu
-            let undo = PredefinedMenuItem::undo(app, None)?;
-            let redo = PredefinedMenuItem::redo(app, None)?;
-            let separator3 = PredefinedMenuItem::separator(app)?;
-            let cut = PredefinedMenuItem::cut(app, None)?;
-            let copy = PredefinedMenuItem::copy(app, None)?;
-            let paste = PredefinedMenuItem::paste(app, None)?;
-            let select_all = PredefinedMenuItem::select_all(app, None)?;
-            let edit_submenu = Submenu::with_items(
-                app,
-                "Edit",
-                true,
-                &[&undo, &redo, &separator3, &cut, &copy, &paste, &select_all],
-            )?;
-
-            // Window menu
-            let minimize = PredefinedMenuItem::minimize(app, None)?;
-            let fullscreen = PredefinedMenuItem::fullscreen(app, Some("Enter Full Screen"))?;
-            let separator4 = PredefinedMenuItem::separator(app)?;
-            let close_window = PredefinedMenuItem::close_window(app, None)?;
-            let window_submenu = Submenu::with_items(
-                app,
-                "Window",
-                true,
-                &[&minimize, &fullscreen, &separator4, &close_window],
-            )?;
-
-            let menu = Menu::with_items(app, &[&app_submenu, &edit_submenu, &window_submenu])?;
-            app.set_menu(menu)?;
-            Ok(())
-        })
-        .on_menu_event(|app, event| {
-            if event.id().as_ref() == "check_updates" {
-                let app_handle = app.clone();
-                tauri::async_runtime::spawn(async move {
-                    match app_handle.updater() {
-                        Ok(updater) => {
-                            match updater.check().await {
-                                Ok(Some(update)) => {
-                                    let version = update.version.clone();
-                                    let msg = format!("Version {} is available. Would you like to install it now?", version);
-                                    let confirmed = app_handle.dialog()
-                                        .message(msg)
-                                        .title("Update Available")
-                                        .buttons(MessageDialogButtons::OkCancel)
-                                        .blocking_show();
-
-                                    if confirmed {
-                                        if let Err(e) = update.download_and_install(|_, _| {}, || {}).await {
-                                            app_handle.dialog()
-                                                .message(format!("Failed to install update: {}", e))
-                                                .kind(MessageDialogKind::Error)
-                                                .title("Update Error")
-                                                .blocking_show();
-                                        } else {
-                                            app_handle.dialog()
-                                                .message("Update installed. Please restart the application.")
-                                                .title("Update Complete")
-                                                .blocking_show();
-                                        }
-                                    }
-                                }
-                                Ok(None) => {
-                                    app_handle.dialog()
-                                        .message("You're running the latest version.")
-                                        .title("No Updates")
-                                        .blocking_show();
-                                }
-                                Err(e) => {
-                                    app_handle.dialog()
-                                        .message(format!("Failed to check for updates: {}", e))
-                                        .kind(MessageDialogKind::Error)
-                                        .title("Update Error")
-                                        .blocking_show();
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            app_handle.dialog()
-                                .message(format!("Updater not available: {}", e))
-                                .kind(MessageDialogKind::Error)
-                                .title("Update Error")
-                                .blocking_show();
-                        }
-                    }
-                });
-            } else if event.id().as_ref() == "clear_cache" {
-                let app_handle = app.clone();
-                let confirmed = app_handle.dialog()
-                    .message("This will clear all cached data and restart the app. You may need to log in again. Continue?")
-                    .title("Clear Cache")
-                    .buttons(MessageDialogButtons::OkCancel)
-                    .blocking_show();
-
-                if confirmed {
-                    // Clear WebView data
-                    if let Some(window) = app_handle.get_webview_window("main") {
-                        let _ = window.clear_all_browsing_data();
-                    }
-                    // Restart the app
-                    app_handle.restart();
+        .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, None))
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::CloseRequested { api, .. } if window.label() == "main" => {
+                window_state::save(window);
+                if tray::hides_on_close(window.app_handle()) {
+                    api.prevent_close();
+                    let _ = window.hide();
+                } else if unsaved::is_dirty(window.app_handle()) {
+                    api.prevent_close();
+                    let window = window.clone();
+                    unsaved::confirm_discard(window.app_handle(), move |_| {
+                        let _ = window.close();
+                    });
                 }
             }
-        });
+            tauri::WindowEvent::Resized(_) => windows::resized(window),
+            tauri::WindowEvent::ThemeChanged(os_theme) if window.label() == "main" => {
+                theme::os_theme_changed(window.app_handle(), *os_theme);
+            }
+            tauri::WindowEvent::Focused(focused) => {
+                if *focused {
+                    windows::sync_fullscreen_item(window);
+                } else {
+                    windows::end_menu_peek(window);
+                }
+                menu::refresh_window_list(window.app_handle(), None);
+            }
+            tauri::WindowEvent::Destroyed => {
+                windows::forget_window(window);
+                menu::refresh_window_list(window.app_handle(), Some(window.label()));
+            }
+            _ => {}
+        })
+        .on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()));
+
+    let commands = tauri::generate_handler![
+        greet,
+        greet_async,
+        app_version,
+        open_config_dir,
+        settings::get_settings,
+        settings::set_settings,
+        settings::set_update_channel,
+        deep_link::take_pending_deep_link,
+        cache::clear_cache,
+        changelog::changelog,
+        diagnostics::platform_info,
+        diagnostics::copy_diagnostics,
+        diagnostics::plugin_health,
+        crash::get_last_crash,
+        logging::flush_logs,
+        network::is_online,
+        feature_flags::feature_flags,
+        notifications::ensure_notification_permission,
+        notifications::send_test_notification,
+        notifications::notification_actioned,
+        schedule::schedule_notification,
+        schedule::cancel_scheduled,
+        kv::kv_get,
+        kv::kv_set,
+        kv::kv_delete,
+        unsaved::set_dirty,
+        startup::frontend_ready,
+        links::open_external,
+        #[cfg(desktop)]
+        user_agent::user_agent,
+        #[cfg(desktop)]
+        deep_link::registered_schemes,
+        #[cfg(desktop)]
+        deep_link::repair_schemes,
+        #[cfg(desktop)]
+        settings::export_settings,
+        #[cfg(desktop)]
+        settings::import_settings,
+        #[cfg(desktop)]
+        updater::update_status,
+        #[cfg(desktop)]
+        updater::update_details,
+        #[cfg(desktop)]
+        updater::ping_update_server,
+        #[cfg(desktop)]
+        updater::cancel_update,
+        #[cfg(desktop)]
+        updater::pause_update,
+        #[cfg(desktop)]
+        updater::resume_update,
+        #[cfg(desktop)]
+        updater::free_disk_space,
+        #[cfg(desktop)]
+        updater::launched_after_update,
+        #[cfg(desktop)]
+        badge::set_badge,
+        #[cfg(desktop)]
+        progress::set_progress,
+        #[cfg(desktop)]
+        feedback::prepare_feedback_bundle,
+        #[cfg(desktop)]
+        capture::capture_window,
+        #[cfg(desktop)]
+        windows::set_always_on_top,
+        #[cfg(desktop)]
+        windows::set_window_opacity,
+        #[cfg(desktop)]
+        zoom::get_zoom,
+        #[cfg(desktop)]
+        zoom::set_zoom,
+        #[cfg(desktop)]
+        windows::toggle_fullscreen,
+        #[cfg(desktop)]
+        windows::set_decorations,
+        #[cfg(desktop)]
+        windows::window_minimize,
+        #[cfg(desktop)]
+        windows::window_toggle_maximize,
+        #[cfg(desktop)]
+        windows::window_close,
+        #[cfg(desktop)]
+        windows::list_monitors,
+        #[cfg(desktop)]
+        windows::move_to_monitor,
+        #[cfg(desktop)]
+        windows::set_menu_visible,
+        #[cfg(desktop)]
+        windows::peek_menu,
+        #[cfg(desktop)]
+        windows::list_windows,
+        #[cfg(desktop)]
+        windows::focus_window,
+        #[cfg(desktop)]
+        windows::open_in_app,
+        #[cfg(desktop)]
+        autostart::set_autostart,
+        #[cfg(desktop)]
+        autostart::get_autostart,
+        #[cfg(desktop)]
+        documents::take_opened_files,
+        #[cfg(desktop)]
+        documents::pick_and_read_file,
+        #[cfg(desktop)]
+        lifecycle::restart_app,
+        #[cfg(desktop)]
+        rollback::report_startup_ok,
+        #[cfg(debug_assertions)]
+        feature_flags::set_feature_flag,
+        #[cfg(all(desktop, debug_assertions))]
+        idle::idle_seconds,
+        #[cfg(all(desktop, any(debug_assertions, feature = "devtools")))]
+        windows::toggle_devtools,
+    ];
 
     builder
-        .invoke_handler(tauri::generate_handler![greet])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .invoke_handler(move |invoke| audit::dispatch(invoke, &commands))
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            // Covers the Quit menu item and tray Quit as well as closing the last window.
+            #[cfg(desktop)]
+            tauri::RunEvent::ExitRequested { api, .. } if unsaved::is_dirty(app) => {
+                api.prevent_exit();
+                unsaved::confirm_discard(app, |app| app.exit(0));
+            }
+            // The last window closed (an explicit exit carries a code); stay running if
+            // the user asked to.
+            #[cfg(desktop)]
+            tauri::RunEvent::ExitRequested { code: None, api, .. } if !settings::current(app).quit_on_last_window_close => {
+                api.prevent_exit();
+            }
+            // Not held back by either prompt above; the main window is still open here.
+            #[cfg(desktop)]
+            tauri::RunEvent::ExitRequested { .. } => window_state::save_main(app),
+            // Past every prompt above, so the app really is going away.
+            tauri::RunEvent::Exit => {
+                lifecycle::flush_state(app);
+                #[cfg(desktop)]
+                updater::relaunch_if_requested(app);
+            }
+            // Mobile apps are usually killed in the background rather than exited, so
+            // state is flushed whenever the app is sent there.
+            #[cfg(mobile)]
+            tauri::RunEvent::WindowEvent { event: tauri::WindowEvent::Focused(false), .. } => lifecycle::flush_state(app),
+            // Clicking the Dock icon while the main window is hidden.
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Reopen { has_visible_windows: false, .. } => tray::show_main_window(app),
+            // "Open With" and double-clicks in Finder arrive as file URLs rather than argv.
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Opened { urls } => {
+                let paths: Vec<_> = urls.iter().filter_map(|url| url.to_file_path().ok()).collect();
+                documents::open_paths(app, &paths);
+            }
+            _ => {}
+        });
 }
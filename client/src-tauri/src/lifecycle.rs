@@ -0,0 +1,55 @@
+use std::time::Duration;
+use tauri::AppHandle;
+#[cfg(desktop)]
+use tauri::Emitter;
+
+#[cfg(desktop)]
+use crate::{events, unsaved, window_state};
+use crate::{kv, logging, settings};
+
+// How long the frontend gets to persist its state after `app://restarting`.
+#[cfg(desktop)]
+const RESTART_GRACE: Duration = Duration::from_millis(500);
+// Longest the process waits on `flush_state` before exiting anyway.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Writes settings (a failed write-through leaves them only in memory) and the main
+// window's geometry, waits out a key-value write still in flight, then flushes the
+// logs. Runs on its own thread so a stalled disk can't hold up exit.
+pub fn flush_state(app: &AppHandle) {
+    #[cfg(desktop)]
+    window_state::save_main(app);
+    let (tx, rx) = std::sync::mpsc::channel();
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = settings::save(&app, &settings::current(&app)) {
+            log::warn!("Failed to flush settings: {}", e);
+        }
+        kv::wait_for_writes(&app);
+        logging::flush();
+        let _ = tx.send(());
+    });
+    if rx.recv_timeout(FLUSH_TIMEOUT).is_err() {
+        log::warn!("Gave up waiting for state to flush after {:?}", FLUSH_TIMEOUT);
+        logging::flush();
+    }
+}
+
+// Relaunches the app cleanly: tells the frontend, writes settings out, then restarts.
+#[cfg(desktop)]
+pub async fn restart(app: AppHandle) {
+    log::info!("Restarting");
+    let _ = app.emit(events::APP_RESTARTING, ());
+    tokio::time::sleep(RESTART_GRACE).await;
+
+    flush_state(&app);
+    // The frontend has had its chance to save; don't stop on the unsaved-changes prompt.
+    unsaved::discard(&app);
+    app.restart();
+}
+
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn restart_app(app: AppHandle) {
+    restart(app).await;
+}
@@ -0,0 +1,93 @@
+use log::LevelFilter;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{App, AppHandle, Manager};
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
+
+use crate::audit;
+use crate::settings::{AuditLevel, Settings};
+
+// Overrides the log level, e.g. `ANGMOM_LOG=debug`.
+const LOG_LEVEL_ENV: &str = "ANGMOM_LOG";
+// Rotate at 5 MB and keep the five most recent files.
+const MAX_LOG_FILE_BYTES: u128 = 5 * 1024 * 1024;
+const KEPT_LOG_FILES: usize = 5;
+
+fn level(debug_logging: bool) -> LevelFilter {
+    let from_env = std::env::var(LOG_LEVEL_ENV)
+        .ok()
+        .and_then(|value| value.parse::<LevelFilter>().ok());
+    match from_env {
+        Some(level) => level,
+        None if debug_logging => LevelFilter::Debug,
+        None => LevelFilter::Info,
+    }
+}
+
+fn audit_level(level: AuditLevel) -> LevelFilter {
+    match level {
+        AuditLevel::Off => LevelFilter::Off,
+        AuditLevel::Error => LevelFilter::Error,
+        AuditLevel::Warn => LevelFilter::Warn,
+        AuditLevel::Info => LevelFilter::Info,
+        AuditLevel::Debug => LevelFilter::Debug,
+        AuditLevel::Trace => LevelFilter::Trace,
+    }
+}
+
+// Logs to stdout and to a rotating file in the app log dir. IPC audit records go
+// to their own `ipc-audit` file instead.
+pub fn init(app: &App, settings: &Settings) -> tauri::Result<()> {
+    app.handle().plugin(
+        tauri_plugin_log::Builder::new()
+            .level(level(settings.debug_logging))
+            .level_for(audit::TARGET, audit_level(settings.ipc_audit_level))
+            .targets([
+                Target::new(TargetKind::Stdout).filter(|metadata| metadata.target() != audit::TARGET),
+                Target::new(TargetKind::LogDir { file_name: None }).filter(|metadata| metadata.target() != audit::TARGET),
+                Target::new(TargetKind::LogDir { file_name: Some("ipc-audit".into()) })
+                    .filter(|metadata| metadata.target() == audit::TARGET),
+            ])
+            .max_file_size(MAX_LOG_FILE_BYTES)
+            .rotation_strategy(RotationStrategy::KeepSome(KEPT_LOG_FILES))
+            .build(),
+    )
+}
+
+// The file the default `LogDir` target writes to, named after the app.
+pub fn active_log_file(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_log_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(format!("{}.log", app.package_info().name)))
+}
+
+// Pushes any buffered lines out to the log files.
+pub fn flush() {
+    log::logger().flush();
+}
+
+// Moves everything logged so far into a timestamped file next to the active one,
+// then empties the active one. The plugin holds it open in append mode, so new
+// lines continue at the start of the emptied file. A line written between the
+// copy and the truncate is lost.
+fn start_fresh(path: &Path) -> std::io::Result<PathBuf> {
+    let stamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    let archived = path.with_file_name(format!("{}_{}.log", stem, stamp));
+    fs::copy(path, &archived)?;
+    fs::OpenOptions::new().write(true).open(path)?.set_len(0)?;
+    Ok(archived)
+}
+
+// Flushes the logs so a bug report taken now is complete, optionally starting a
+// fresh file. Returns the active log file's path.
+#[tauri::command]
+pub fn flush_logs(app: AppHandle, rotate: Option<bool>) -> Result<String, String> {
+    flush();
+    let path = active_log_file(&app)?;
+    if rotate.unwrap_or(false) && path.exists() {
+        let archived = start_fresh(&path).map_err(|e| format!("Couldn't start a fresh log file: {}", e))?;
+        log::info!("Started a fresh log file; earlier lines are in {}", archived.display());
+    }
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,269 @@
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, MenuItemKind, PredefinedMenuItem, Submenu};
+use tauri::{App, AppHandle, Wry};
+
+use crate::i18n::t;
+use crate::settings::{self, ThemePreference};
+use crate::{about, autostart, theme, windows};
+
+const WINDOW_MENU_ID: &str = "window_menu";
+// Window menu entries for open windows are `window_item:<label>`.
+pub const WINDOW_ITEM_PREFIX: &str = "window_item:";
+
+// Builds the menu bar one submenu at a time. A submenu that fails to build is
+// logged and left out rather than aborting startup; if the app menu itself fails,
+// a bare one with Quit stands in so the app can always be quit from the menu.
+pub fn create(app: &App) {
+    let builders: [(&str, fn(&App) -> tauri::Result<Submenu<Wry>>); 6] = [
+        ("app", app_submenu),
+        ("File", file_submenu),
+        ("Edit", edit_submenu),
+        ("View", view_submenu),
+        ("Window", window_submenu),
+        ("Help", help_submenu),
+    ];
+    let mut submenus = Vec::new();
+    for (name, build) in builders {
+        match build(app) {
+            Ok(submenu) => submenus.push(submenu),
+            Err(e) if name == "app" => {
+                log::error!("Failed to build the app menu; using a minimal one: {}", e);
+                match minimal_app_submenu(app) {
+                    Ok(submenu) => submenus.push(submenu),
+                    Err(e) => log::error!("Failed to build the minimal app menu: {}", e),
+                }
+            }
+            Err(e) => log::error!("Failed to build the {} menu; leaving it out: {}", name, e),
+        }
+    }
+
+    let items: Vec<&dyn IsMenuItem<Wry>> = submenus.iter().map(|submenu| submenu as &dyn IsMenuItem<Wry>).collect();
+    let result = Menu::with_items(app, &items).and_then(|menu| app.set_menu(menu));
+    if let Err(e) = result {
+        log::error!("Failed to set the app menu: {}", e);
+    }
+}
+
+fn minimal_app_submenu(app: &App) -> tauri::Result<Submenu<Wry>> {
+    let quit = PredefinedMenuItem::quit(app, Some(t("quit")))?;
+    Submenu::with_items(app, "Angular Momentum", true, &[&quit])
+}
+
+fn app_submenu(app: &App) -> tauri::Result<Submenu<Wry>> {
+    #[cfg(target_os = "macos")]
+    let about = PredefinedMenuItem::about(app, Some(t("about")), Some(about::metadata(app.handle())))?;
+    #[cfg(not(target_os = "macos"))]
+    let about = MenuItem::with_id(app, "about", t("about"), true, None::<&str>)?;
+    let separator_about = PredefinedMenuItem::separator(app)?;
+    let check_updates = MenuItem::with_id(app, "check_updates", t("check_updates"), true, None::<&str>)?;
+    let preferences = MenuItem::with_id(app, "preferences", t("preferences"), true, Some("CmdOrCtrl+,"))?;
+    let launch_at_login = CheckMenuItem::with_id(
+        app,
+        autostart::MENU_ID,
+        t("launch_at_login"),
+        true,
+        autostart::is_enabled(app.handle()),
+        None::<&str>,
+    )?;
+    let clear_cache = MenuItem::with_id(app, "clear_cache", t("clear_cache_restart"), true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let hide = PredefinedMenuItem::hide(app, Some(t("hide")))?;
+    let hide_others = PredefinedMenuItem::hide_others(app, Some(t("hide_others")))?;
+    let show_all = PredefinedMenuItem::show_all(app, Some(t("show_all")))?;
+    let separator2 = PredefinedMenuItem::separator(app)?;
+    let quit = PredefinedMenuItem::quit(app, Some(t("quit")))?;
+    Submenu::with_items(
+        app,
+        "Angular Momentum",
+        true,
+        &[&about, &separator_about, &check_updates, &preferences, &launch_at_login, &clear_cache, &separator, &hide, &hide_others, &show_all, &separator2, &quit],
+    )
+}
+
+fn file_submenu(app: &App) -> tauri::Result<Submenu<Wry>> {
+    let new_window = MenuItem::with_id(app, "new_window", "New Window", true, Some("CmdOrCtrl+N"))?;
+    let separator_file = PredefinedMenuItem::separator(app)?;
+    let close_file = PredefinedMenuItem::close_window(app, None)?;
+    Submenu::with_items(app, "File", true, &[&new_window, &separator_file, &close_file])
+}
+
+fn edit_submenu(app: &App) -> tauri::Result<Submenu<Wry>> {
+    let undo = PredefinedMenuItem::undo(app, Some(t("undo")))?;
+    let redo = PredefinedMenuItem::redo(app, Some(t("redo")))?;
+    let separator3 = PredefinedMenuItem::separator(app)?;
+    let cut = PredefinedMenuItem::cut(app, Some(t("cut")))?;
+    let copy = PredefinedMenuItem::copy(app, Some(t("copy")))?;
+    let paste = PredefinedMenuItem::paste(app, Some(t("paste")))?;
+    let select_all = PredefinedMenuItem::select_all(app, Some(t("select_all")))?;
+    Submenu::with_items(
+        app,
+        t("edit"),
+        true,
+        &[&undo, &redo, &separator3, &cut, &copy, &paste, &select_all],
+    )
+}
+
+fn view_submenu(app: &App) -> tauri::Result<Submenu<Wry>> {
+    let zoom_in = MenuItem::with_id(app, "zoom_in", "Zoom In", true, Some("CmdOrCtrl+="))?;
+    let zoom_out = MenuItem::with_id(app, "zoom_out", "Zoom Out", true, Some("CmdOrCtrl+-"))?;
+    let zoom_reset = MenuItem::with_id(app, "zoom_reset", "Actual Size", true, Some("CmdOrCtrl+0"))?;
+    let separator_view = PredefinedMenuItem::separator(app)?;
+    let current_theme = settings::current(app.handle()).theme;
+    let theme_item = |preference: ThemePreference, label: &str| {
+        CheckMenuItem::with_id(app, theme::menu_id(preference), label, true, preference == current_theme, None::<&str>)
+    };
+    let theme_system = theme_item(ThemePreference::System, "System")?;
+    let theme_light = theme_item(ThemePreference::Light, "Light")?;
+    let theme_dark = theme_item(ThemePreference::Dark, "Dark")?;
+    let theme_submenu = Submenu::with_items(app, "Theme", true, &[&theme_system, &theme_light, &theme_dark])?;
+    let view_submenu = Submenu::with_items(
+        app,
+        "View",
+        true,
+        &[&zoom_in, &zoom_out, &zoom_reset, &separator_view, &theme_submenu],
+    )?;
+    // Developer conveniences, left out of release builds.
+    #[cfg(debug_assertions)]
+    {
+        let separator_reload = PredefinedMenuItem::separator(app)?;
+        let reload = MenuItem::with_id(app, "reload", "Reload", true, Some("CmdOrCtrl+R"))?;
+        let force_reload = MenuItem::with_id(app, "force_reload", "Force Reload", true, Some("CmdOrCtrl+Shift+R"))?;
+        view_submenu.append_items(&[&separator_reload, &reload, &force_reload])?;
+    }
+    #[cfg(any(debug_assertions, feature = "devtools"))]
+    {
+        let devtools = MenuItem::with_id(app, "toggle_devtools", "Toggle Developer Tools", true, Some("CmdOrCtrl+Alt+I"))?;
+        view_submenu.append(&devtools)?;
+    }
+    Ok(view_submenu)
+}
+
+fn window_submenu(app: &App) -> tauri::Result<Submenu<Wry>> {
+    let minimize = PredefinedMenuItem::minimize(app, Some(t("minimize")))?;
+    #[cfg(target_os = "macos")]
+    let fullscreen_accelerator = "Ctrl+Cmd+F";
+    #[cfg(not(target_os = "macos"))]
+    let fullscreen_accelerator = "F11";
+    let fullscreen = CheckMenuItem::with_id(app, windows::FULLSCREEN_MENU_ID, t("full_screen"), true, false, Some(fullscreen_accelerator))?;
+    let keep_on_top = CheckMenuItem::with_id(
+        app,
+        windows::KEEP_ON_TOP_MENU_ID,
+        t("keep_on_top"),
+        true,
+        settings::current(app.handle()).always_on_top,
+        None::<&str>,
+    )?;
+    let reset_window = MenuItem::with_id(app, "reset_window", t("reset_window"), true, None::<&str>)?;
+    let separator4 = PredefinedMenuItem::separator(app)?;
+    let close_window = PredefinedMenuItem::close_window(app, Some(t("close_window")))?;
+    let window_submenu = Submenu::with_id_and_items(
+        app,
+        WINDOW_MENU_ID,
+        t("window"),
+        true,
+        &[&minimize, &fullscreen, &keep_on_top, &reset_window, &separator4, &close_window],
+    )?;
+    #[cfg(target_os = "macos")]
+    {
+        let separator_front = PredefinedMenuItem::separator(app)?;
+        let bring_all_to_front = MenuItem::with_id(app, "bring_all_to_front", t("bring_all_to_front"), true, None::<&str>)?;
+        window_submenu.append_items(&[&separator_front, &bring_all_to_front])?;
+    }
+    // Open windows are listed below this by `refresh_window_list`.
+    window_submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    Ok(window_submenu)
+}
+
+fn help_submenu(app: &App) -> tauri::Result<Submenu<Wry>> {
+    let documentation = MenuItem::with_id(app, "documentation", "Documentation", true, None::<&str>)?;
+    let whats_new = MenuItem::with_id(app, windows::WHATS_NEW_MENU_ID, "What's New", true, None::<&str>)?;
+    let report_issue = MenuItem::with_id(app, "report_issue", "Report an Issue...", true, None::<&str>)?;
+    let send_feedback = MenuItem::with_id(app, "send_feedback", "Send Feedback...", true, None::<&str>)?;
+    let help_check_updates = MenuItem::with_id(app, "help_check_updates", t("check_updates"), true, None::<&str>)?;
+    let separator_help = PredefinedMenuItem::separator(app)?;
+    let reveal_config = MenuItem::with_id(app, "reveal_config", "Reveal Config Folder", true, None::<&str>)?;
+    let copy_diagnostics = MenuItem::with_id(app, "copy_diagnostics", "Copy Diagnostics to Clipboard", true, None::<&str>)?;
+    let test_notification = MenuItem::with_id(app, "test_notification", "Send Test Notification", true, None::<&str>)?;
+    let clear_app_cache = MenuItem::with_id(app, "clear_app_cache", "Clear Cached Files...", true, None::<&str>)?;
+    Submenu::with_items(
+        app,
+        "Help",
+        true,
+        &[&documentation, &whats_new, &report_issue, &send_feedback, &help_check_updates, &separator_help, &reveal_config, &copy_diagnostics, &test_notification, &clear_app_cache],
+    )
+}
+
+// The menu bar of an in-app browser window (Windows/Linux only).
+#[cfg(not(target_os = "macos"))]
+pub fn browser_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let back = MenuItem::with_id(app, windows::BACK_TO_APP_MENU_ID, "Back to App", true, Some("CmdOrCtrl+W"))?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let copy = PredefinedMenuItem::copy(app, Some(t("copy")))?;
+    let select_all = PredefinedMenuItem::select_all(app, Some(t("select_all")))?;
+    let page_submenu = Submenu::with_items(app, "Page", true, &[&back, &separator, &copy, &select_all])?;
+    Menu::with_items(app, &[&page_submenu])
+}
+
+fn find_check_item(items: Vec<MenuItemKind<Wry>>, id: &str) -> Option<CheckMenuItem<Wry>> {
+    for item in items {
+        match item {
+            MenuItemKind::Check(check) if check.id().as_ref() == id => return Some(check),
+            MenuItemKind::Submenu(submenu) => {
+                if let Some(found) = submenu.items().ok().and_then(|items| find_check_item(items, id)) {
+                    return Some(found);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// Looks up a checkable item anywhere in the app menu, for keeping its state in sync.
+pub fn check_item(app: &AppHandle, id: &str) -> Option<CheckMenuItem<Wry>> {
+    find_check_item(app.menu()?.items().ok()?, id)
+}
+
+fn find_item(items: Vec<MenuItemKind<Wry>>, id: &str) -> Option<MenuItem<Wry>> {
+    for item in items {
+        match item {
+            MenuItemKind::MenuItem(item) if item.id().as_ref() == id => return Some(item),
+            MenuItemKind::Submenu(submenu) => {
+                if let Some(found) = submenu.items().ok().and_then(|items| find_item(items, id)) {
+                    return Some(found);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// Looks up a plain item anywhere in the app menu, for enabling or disabling it.
+pub fn item(app: &AppHandle, id: &str) -> Option<MenuItem<Wry>> {
+    find_item(app.menu()?.items().ok()?, id)
+}
+
+// Rebuilds the list of open windows at the bottom of the Window menu, checking
+// the focused one. `closing` is left out because it may still be registered
+// while its Destroyed event is handled.
+pub fn refresh_window_list(app: &AppHandle, closing: Option<&str>) {
+    let Some(MenuItemKind::Submenu(submenu)) = app.menu().and_then(|menu| menu.get(WINDOW_MENU_ID)) else { return };
+    if let Ok(items) = submenu.items() {
+        for item in items.iter().filter(|item| item.id().as_ref().starts_with(WINDOW_ITEM_PREFIX)) {
+            let _ = submenu.remove(item);
+        }
+    }
+    for window in windows::list(app) {
+        if Some(window.label.as_str()) == closing {
+            continue;
+        }
+        let id = format!("{}{}", WINDOW_ITEM_PREFIX, window.label);
+        let title = if window.title.is_empty() { window.label.clone() } else { window.title };
+        match CheckMenuItem::with_id(app, id, title, true, window.focused, None::<&str>) {
+            Ok(item) => {
+                let _ = submenu.append(&item);
+            }
+            Err(e) => log::warn!("Failed to add window menu item: {}", e),
+        }
+    }
+}
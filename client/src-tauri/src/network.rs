@@ -0,0 +1,95 @@
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{App, AppHandle, Emitter, Manager, State};
+
+use crate::{events, settings};
+
+// Reachability is judged by opening a TCP connection to the host updates come
+// from: one connect per poll, no request sent. Mobile has no updater to ask.
+#[cfg(mobile)]
+const PROBE_HOST: (&str, u16) = ("github.com", 443);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+// Only the first few resolved addresses are tried, so a dead network fails fast.
+const PROBE_ADDRESSES: usize = 2;
+const MIN_POLL_SECONDS: u64 = 5;
+
+// Last probe result. Starts optimistic so nothing is held back before the first probe.
+pub struct Connectivity(AtomicBool);
+
+// The update endpoint's host, so a self-hosted mirror or a network that blocks
+// GitHub is judged by the server the app actually talks to.
+#[cfg(desktop)]
+fn probe_host(app: &AppHandle) -> Option<(String, u16)> {
+    let url = crate::updater::update_endpoint(&settings::current(app)).ok()?;
+    Some((url.host_str()?.to_string(), url.port_or_known_default()?))
+}
+
+#[cfg(mobile)]
+fn probe_host(_app: &AppHandle) -> Option<(String, u16)> {
+    Some((PROBE_HOST.0.to_string(), PROBE_HOST.1))
+}
+
+fn probe(host: &(String, u16)) -> bool {
+    let Ok(addresses) = (host.0.as_str(), host.1).to_socket_addrs() else { return false };
+    addresses
+        .take(PROBE_ADDRESSES)
+        .any(|address| TcpStream::connect_timeout(&address, PROBE_TIMEOUT).is_ok())
+}
+
+// Stores a probe result, returning whether it differs from the last one.
+fn record(state: &AtomicBool, online: bool) -> bool {
+    state.swap(online, Ordering::Relaxed) != online
+}
+
+pub fn current(app: &AppHandle) -> bool {
+    app.state::<Connectivity>().0.load(Ordering::Relaxed)
+}
+
+// Probes again, announcing a flip with `network://changed`.
+pub async fn refresh(app: &AppHandle) -> bool {
+    // Without a host to probe there's nothing to say the network is down.
+    let Some(host) = probe_host(app) else { return current(app) };
+    let online = tauri::async_runtime::spawn_blocking(move || probe(&host)).await.unwrap_or(false);
+    if record(&app.state::<Connectivity>().0, online) {
+        log::info!("Network is now {}", if online { "online" } else { "offline" });
+        let _ = app.emit(events::NETWORK_CHANGED, events::NetworkChanged(online));
+    }
+    online
+}
+
+// Polls every `network_poll_seconds`; 0 turns polling off and leaves the app
+// assuming it's online.
+pub fn init(app: &App) {
+    app.manage(Connectivity(AtomicBool::new(true)));
+    let app = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let seconds = settings::current(&app).network_poll_seconds;
+            if seconds > 0 {
+                refresh(&app).await;
+            }
+            tokio::time::sleep(Duration::from_secs(seconds.max(MIN_POLL_SECONDS))).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub fn is_online(state: State<'_, Connectivity>) -> bool {
+    state.0.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_reports_only_flips() {
+        let state = AtomicBool::new(true);
+        assert!(!record(&state, true));
+        assert!(record(&state, false));
+        assert!(!record(&state, false));
+        assert!(record(&state, true));
+        assert!(state.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use tauri::plugin::PermissionState;
+use tauri::{AppHandle, Emitter, Url};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::{deep_link, events};
+
+// Key in a notification's `extra` data holding the deep link its click follows.
+pub const ACTION_KEY: &str = "action";
+
+// Requests notification permission if the user hasn't been asked yet.
+pub fn ensure_permission(app: &AppHandle) -> Result<bool, String> {
+    let notification = app.notification();
+    let state = match notification.permission_state().map_err(|e| e.to_string())? {
+        PermissionState::Prompt | PermissionState::PromptWithRationale => {
+            notification.request_permission().map_err(|e| e.to_string())?
+        }
+        state => state,
+    };
+
+    let granted = matches!(state, PermissionState::Granted);
+    if !granted {
+        log::warn!("Notification permission denied; update notices fall back to dialogs");
+    }
+    Ok(granted)
+}
+
+// Whether notifications can be shown right now. Checked alongside the user's
+// `update_notifications` preference rather than written into it, so granting
+// permission later in the system settings brings them back.
+pub fn permitted(app: &AppHandle) -> bool {
+    matches!(app.notification().permission_state(), Ok(PermissionState::Granted))
+}
+
+// Asks in the background so startup never waits on the OS prompt.
+pub fn request_on_launch(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = ensure_permission(&app) {
+            log::warn!("Failed to query notification permission: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn ensure_notification_permission(app: AppHandle) -> Result<bool, String> {
+    ensure_permission(&app)
+}
+
+// Lets users confirm notifications reach them. Errors when permission is denied so
+// the frontend can explain why nothing appeared.
+pub fn send_test(app: &AppHandle) -> Result<(), String> {
+    if !ensure_permission(app)? {
+        return Err("Notification permission was denied. Enable notifications for Angular Momentum in your system settings.".into());
+    }
+    app.notification()
+        .builder()
+        .title("Test Notification")
+        .body("Notifications from Angular Momentum are working.")
+        .show()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn send_test_notification(app: AppHandle) -> Result<(), String> {
+    send_test(&app)
+}
+
+// Called from the frontend's notification `onAction` listener, which is how clicks
+// reach the app on mobile, including the click that launched it. The frontend hears
+// about it via `notification://actioned`, and a notification carrying an action
+// follows it through the deep-link router. The desktop notification backends don't
+// report clicks at all, so desktop notifications (update ones included) carry no
+// action and tell the user what to do instead.
+#[tauri::command]
+pub fn notification_actioned(app: AppHandle, id: i32, extra: HashMap<String, serde_json::Value>) -> Result<(), String> {
+    let action = extra.get(ACTION_KEY).and_then(|action| action.as_str()).map(str::to_string);
+    log::info!("Notification {} actioned ({:?})", id, action);
+    let _ = app.emit(events::NOTIFICATION_ACTIONED, events::NotificationActioned { id, action: action.clone() });
+    if let Some(action) = action {
+        let url = Url::parse(&action).map_err(|e| format!("Invalid notification action {}: {}", action, e))?;
+        deep_link::handle_url(&app, &url);
+    }
+    Ok(())
+}
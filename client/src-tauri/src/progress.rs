@@ -0,0 +1,38 @@
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::WebviewWindow;
+
+// Progress on the app icon: the taskbar button on Windows, the Dock tile on macOS.
+// `progress` is a fraction, clamped to 0.0–1.0; `None` clears it.
+pub fn set(window: &WebviewWindow, progress: Option<f64>) {
+    let state = match progress {
+        Some(progress) => ProgressBarState {
+            status: Some(ProgressBarStatus::Normal),
+            progress: Some((progress.clamp(0.0, 1.0) * 100.0).round() as u64),
+        },
+        None => ProgressBarState { status: Some(ProgressBarStatus::None), progress: None },
+    };
+    apply(window, state);
+}
+
+// For downloads that don't report a size.
+pub fn indeterminate(window: &WebviewWindow) {
+    apply(window, ProgressBarState { status: Some(ProgressBarStatus::Indeterminate), progress: None });
+}
+
+fn apply(window: &WebviewWindow, state: ProgressBarState) {
+    #[cfg(any(target_os = "macos", windows))]
+    if let Err(e) = window.set_progress_bar(state) {
+        log::warn!("Failed to set app icon progress: {}", e);
+    }
+    // Linux desktops don't share a progress API worth depending on.
+    #[cfg(not(any(target_os = "macos", windows)))]
+    {
+        let _ = window;
+        log::debug!("App icon progress isn't supported on this platform; ignoring {:?}", state.progress);
+    }
+}
+
+#[tauri::command]
+pub fn set_progress(window: WebviewWindow, progress: Option<f64>) {
+    set(&window, progress);
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::{settings, updater};
+
+const MARKER_FILE: &str = "startup.json";
+// Consecutive unconfirmed launches of a new version before a rollback is offered.
+const CRASH_LOOP_THRESHOLD: u32 = 3;
+
+// Tracks whether the running version has ever been seen to work.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct StartupMarker {
+    // Last version the frontend confirmed with `report_startup_ok`.
+    last_good_version: Option<String>,
+    // The version `unconfirmed_launches` counts, so a new version starts from zero.
+    counted_version: Option<String>,
+    // Launches of `counted_version` that never reached `report_startup_ok`.
+    unconfirmed_launches: u32,
+}
+
+fn marker_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(MARKER_FILE))
+}
+
+fn load(app: &AppHandle) -> StartupMarker {
+    marker_path(app)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save(app: &AppHandle, marker: &StartupMarker) {
+    let Some(path) = marker_path(app) else { return };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(marker) {
+        if let Err(e) = fs::write(path, json) {
+            log::warn!("Failed to write startup marker: {}", e);
+        }
+    }
+}
+
+fn current_version(app: &AppHandle) -> String {
+    app.package_info().version.to_string()
+}
+
+// Counts this launch against the running version. Returns the version to offer
+// rolling back to when recent launches of a new version never confirmed startup.
+pub fn record_launch(app: &AppHandle) -> Option<String> {
+    let current = current_version(app);
+    let mut marker = load(app);
+    if marker.last_good_version.as_deref() == Some(current.as_str()) {
+        return None;
+    }
+
+    if marker.counted_version.as_deref() != Some(current.as_str()) {
+        marker.counted_version = Some(current.clone());
+        marker.unconfirmed_launches = 0;
+    }
+    marker.unconfirmed_launches += 1;
+    let crash_loop = marker.unconfirmed_launches > CRASH_LOOP_THRESHOLD;
+    save(app, &marker);
+
+    match marker.last_good_version {
+        Some(previous) if crash_loop => {
+            log::warn!(
+                "Version {} has not started cleanly in {} launches; last good version was {}",
+                current,
+                marker.unconfirmed_launches - 1,
+                previous
+            );
+            Some(previous)
+        }
+        _ => None,
+    }
+}
+
+pub fn offer_rollback(app: &AppHandle, previous: String) {
+    let current = current_version(app);
+    let rolled_back = current.clone();
+    let app_handle = app.clone();
+    app.dialog()
+        .message(format!(
+            "Angular Momentum {} hasn't started successfully after the last update. Reinstall version {}, the last version that worked?",
+            current, previous
+        ))
+        .title("Startup Problem")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(format!("Reinstall {}", previous), format!("Keep {}", current)))
+        .show(move |reinstall| {
+            if reinstall {
+                tauri::async_runtime::spawn(async move {
+                    // Recorded first: some platforms exit from inside the install.
+                    skip_version(&app_handle, Some(rolled_back));
+                    let result = updater::reinstall_version(&app_handle, &previous).await;
+                    if !matches!(result, Ok(updater::UpdateOutcome::Installed)) {
+                        skip_version(&app_handle, None);
+                    }
+                    updater::show_outcome(&app_handle, result);
+                });
+            } else {
+                // Start counting again rather than asking on every launch.
+                let mut marker = load(&app_handle);
+                marker.unconfirmed_launches = 0;
+                save(&app_handle, &marker);
+            }
+        });
+}
+
+// The version rolled back from isn't offered again; a newer release still is.
+fn skip_version(app: &AppHandle, version: Option<String>) {
+    if let Err(e) = settings::update(app, |settings| settings.skipped_update = version) {
+        log::warn!("Failed to record the skipped update: {}", e);
+    }
+}
+
+// Called by the frontend once it has rendered, confirming this version works.
+#[tauri::command]
+pub fn report_startup_ok(app: AppHandle) {
+    let mut marker = load(&app);
+    marker.last_good_version = Some(current_version(&app));
+    marker.unconfirmed_launches = 0;
+    save(&app, &marker);
+}
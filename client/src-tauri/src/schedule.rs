@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{App, AppHandle, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+const SCHEDULE_FILE: &str = "scheduled-notifications.json";
+
+// A notification waiting for its time. `id` doubles as the OS notification id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Scheduled {
+    id: i32,
+    title: String,
+    body: String,
+    at_epoch_ms: i64,
+}
+
+// Pending notifications, mirrored to the app data dir so they survive a restart.
+// The OS only schedules notifications on mobile; desktop keeps a timer per entry
+// and fires it only if the entry is still here when the timer runs out.
+pub struct Schedules {
+    path: Option<PathBuf>,
+    pending: Mutex<Vec<Scheduled>>,
+    next_id: AtomicI32,
+}
+
+impl Schedules {
+    fn save(&self, pending: &[Scheduled]) {
+        let Some(path) = &self.path else { return };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let result = serde_json::to_string(pending).map_err(std::io::Error::from).and_then(|json| fs::write(path, json));
+        if let Err(e) = result {
+            log::warn!("Failed to save scheduled notifications: {}", e);
+        }
+    }
+
+    // Removes `id`, returning whether it was still pending.
+    fn take(&self, id: i32) -> Option<Scheduled> {
+        let mut pending = self.pending.lock().unwrap();
+        let index = pending.iter().position(|scheduled| scheduled.id == id)?;
+        let scheduled = pending.remove(index);
+        self.save(&pending);
+        Some(scheduled)
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0)
+}
+
+fn show(app: &AppHandle, scheduled: &Scheduled) {
+    let result = app.notification().builder().id(scheduled.id).title(&scheduled.title).body(&scheduled.body).show();
+    if let Err(e) = result {
+        log::warn!("Failed to show scheduled notification {}: {}", scheduled.id, e);
+    }
+}
+
+// Waits until the notification is due (straight away if that's already passed).
+#[cfg(desktop)]
+fn arm(app: &AppHandle, scheduled: &Scheduled) {
+    let (app, id) = (app.clone(), scheduled.id);
+    let delay = std::time::Duration::from_millis(scheduled.at_epoch_ms.saturating_sub(now_ms()).max(0) as u64);
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        if let Some(scheduled) = app.state::<Schedules>().take(id) {
+            show(&app, &scheduled);
+        }
+    });
+}
+
+// Hands the notification to the OS scheduler; one that's already due is shown now.
+#[cfg(mobile)]
+fn arm(app: &AppHandle, scheduled: &Scheduled) {
+    if scheduled.at_epoch_ms <= now_ms() {
+        show(app, scheduled);
+        app.state::<Schedules>().take(scheduled.id);
+        return;
+    }
+    let Ok(date) = time::OffsetDateTime::from_unix_timestamp_nanos(i128::from(scheduled.at_epoch_ms) * 1_000_000) else {
+        return;
+    };
+    let result = app
+        .notification()
+        .builder()
+        .id(scheduled.id)
+        .title(&scheduled.title)
+        .body(&scheduled.body)
+        .schedule(tauri_plugin_notification::Schedule::At { date, repeating: false, allow_while_idle: true })
+        .show();
+    if let Err(e) = result {
+        log::warn!("Failed to schedule notification {}: {}", scheduled.id, e);
+    }
+}
+
+// Loads pending notifications. Desktop re-arms them all, so any that fell due
+// while the app was closed fire now; on mobile the OS already holds them, so
+// only entries past their time are dropped.
+pub fn init(app: &App) {
+    let path = app.path().app_data_dir().ok().map(|dir| dir.join(SCHEDULE_FILE));
+    let mut pending: Vec<Scheduled> = path
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    if cfg!(mobile) {
+        pending.retain(|scheduled| scheduled.at_epoch_ms > now_ms());
+    }
+    let next_id = pending.iter().map(|scheduled| scheduled.id).max().unwrap_or(0) + 1;
+    app.manage(Schedules { path, pending: Mutex::new(pending.clone()), next_id: AtomicI32::new(next_id) });
+    #[cfg(desktop)]
+    for scheduled in &pending {
+        arm(app.handle(), scheduled);
+    }
+}
+
+// Returns an id for `cancel_scheduled`. A time in the past fires immediately.
+#[tauri::command]
+pub fn schedule_notification(app: AppHandle, title: String, body: String, at_epoch_ms: i64) -> Result<String, String> {
+    if title.trim().is_empty() {
+        return Err("A notification needs a title".into());
+    }
+    let schedules = app.state::<Schedules>();
+    let scheduled = Scheduled { id: schedules.next_id.fetch_add(1, Ordering::Relaxed), title, body, at_epoch_ms };
+    {
+        let mut pending = schedules.pending.lock().unwrap();
+        pending.push(scheduled.clone());
+        schedules.save(&pending);
+    }
+    arm(&app, &scheduled);
+    log::info!("Scheduled notification {} for {} (unix ms)", scheduled.id, at_epoch_ms);
+    Ok(scheduled.id.to_string())
+}
+
+// Returns whether the notification was still pending.
+#[tauri::command]
+pub fn cancel_scheduled(app: AppHandle, schedules: State<'_, Schedules>, id: String) -> Result<bool, String> {
+    let id: i32 = id.parse().map_err(|_| format!("Unknown scheduling id: {}", id))?;
+    let cancelled = schedules.take(id).is_some();
+    #[cfg(mobile)]
+    if cancelled {
+        app.notification().cancel(vec![id]).map_err(|e| e.to_string())?;
+    }
+    #[cfg(desktop)]
+    let _ = app;
+    Ok(cancelled)
+}
@@ -0,0 +1,405 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+#[cfg(desktop)]
+use crate::error::{CommandError, ErrorCode};
+
+const SETTINGS_FILE: &str = "settings.json";
+// Exported settings carry this so older builds can refuse files they don't understand.
+// Bump it when a change to `Settings` can't be read by earlier versions.
+#[cfg(desktop)]
+const SCHEMA_VERSION: u32 = 1;
+
+#[cfg(desktop)]
+const STABLE_UPDATE_ENDPOINT: &str = "https://github.com/TheGameKnave/angular-momentum/releases/latest/download/latest.json";
+#[cfg(desktop)]
+const BETA_UPDATE_ENDPOINT: &str = "https://github.com/TheGameKnave/angular-momentum/releases/download/beta/latest.json";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    #[cfg(desktop)]
+    pub fn endpoint(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => STABLE_UPDATE_ENDPOINT,
+            UpdateChannel::Beta => BETA_UPDATE_ENDPOINT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreference {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+// Which IPC audit records are written; anything unrecognized audits at info.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditLevel {
+    Off,
+    Error,
+    Warn,
+    #[default]
+    #[serde(other)]
+    Info,
+    Debug,
+    Trace,
+}
+
+// User preferences, persisted as JSON in the app config dir. Missing fields fall
+// back to their defaults so older settings files keep loading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Closing the main window hides it to the tray instead of quitting.
+    pub minimize_to_tray: bool,
+    // Quit once no windows are open. Defaults to the platform convention: off on
+    // macOS, on elsewhere. `minimize_to_tray` takes precedence for the main window.
+    pub quit_on_last_window_close: bool,
+    pub update_channel: UpdateChannel,
+    // Self-hosted update manifest URL, used instead of the channel's endpoint.
+    // `ANGMOM_UPDATE_URL` takes precedence.
+    pub update_url: Option<String>,
+    pub theme: ThemePreference,
+    // Keep the main window above other windows.
+    pub always_on_top: bool,
+    // Main window opacity, 0.2–1.0; with `always_on_top` it makes a floating overlay.
+    pub window_opacity: f64,
+    // Native frame and titlebar on the main window; off for a custom titlebar.
+    pub decorations: bool,
+    // Show the menu bar in app windows (Windows/Linux; the macOS menu bar is global).
+    pub menu_visible: bool,
+    // Webview zoom factor, clamped to 0.5–3.0.
+    pub zoom: f64,
+    // Global accelerator for "Check for Updates..."; `None` disables it.
+    pub update_shortcut: Option<String>,
+    // Global accelerator that shows or hides the main window; `None` disables it.
+    pub toggle_window_shortcut: Option<String>,
+    // Opt-in background update checks.
+    pub auto_check_updates: bool,
+    pub update_interval_hours: u64,
+    // Background installs wait until the system has been idle this long; 0 doesn't wait.
+    pub update_idle_minutes: u64,
+    // Recheck interval suggested by the update manifest's `recheck_hours`; takes
+    // precedence over `update_interval_hours` while set.
+    pub server_recheck_hours: Option<u64>,
+    // Announce background updates with a notification. Left as chosen when permission
+    // is denied; nothing is sent until it's granted.
+    pub update_notifications: bool,
+    // Seconds between connectivity probes; 0 stops probing.
+    pub network_poll_seconds: u64,
+    // Unix timestamp (seconds) of the last successful update check.
+    pub last_update_check: Option<u64>,
+    // Version a rollback moved away from; update checks don't offer it again.
+    pub skipped_update: Option<String>,
+    // Version whose What's New window has been shown; it opens once per update.
+    pub last_seen_whatsnew: Option<String>,
+    // Appended to the webview user agent after `AngularMomentum/<version>`; applied at launch.
+    pub user_agent_suffix: Option<String>,
+    // Log at debug level; `ANGMOM_LOG` overrides this.
+    pub debug_logging: bool,
+    // Level for the IPC audit log ("off", "info", "debug", ...).
+    pub ipc_audit_level: AuditLevel,
+    // Start the app when the user logs in (desktop only).
+    pub launch_at_login: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            minimize_to_tray: false,
+            quit_on_last_window_close: !cfg!(target_os = "macos"),
+            update_channel: UpdateChannel::default(),
+            update_url: None,
+            theme: ThemePreference::default(),
+            always_on_top: false,
+            window_opacity: 1.0,
+            decorations: true,
+            menu_visible: true,
+            zoom: 1.0,
+            update_shortcut: Some("CmdOrCtrl+U".into()),
+            toggle_window_shortcut: Some("CmdOrCtrl+Shift+Space".into()),
+            auto_check_updates: false,
+            update_interval_hours: 24,
+            update_idle_minutes: 10,
+            server_recheck_hours: None,
+            update_notifications: true,
+            network_poll_seconds: 30,
+            last_update_check: None,
+            skipped_update: None,
+            last_seen_whatsnew: None,
+            user_agent_suffix: None,
+            debug_logging: false,
+            ipc_audit_level: AuditLevel::default(),
+            launch_at_login: false,
+        }
+    }
+}
+
+fn settings_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(SETTINGS_FILE))
+}
+
+// How `load` came by its settings. Reported separately because `load` runs before
+// logging is set up.
+pub enum LoadStatus {
+    Loaded,
+    Missing,
+    // The file couldn't be read or parsed; it was moved aside and defaults used.
+    Corrupt(String),
+}
+
+pub fn load(app: &AppHandle) -> (Settings, LoadStatus) {
+    let Some(path) = settings_path(app) else {
+        return (Settings::default(), LoadStatus::Corrupt("app config dir unavailable".into()));
+    };
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (Settings::default(), LoadStatus::Missing),
+        Err(e) => return (Settings::default(), LoadStatus::Corrupt(e.to_string())),
+    };
+    match serde_json::from_str(&contents) {
+        Ok(settings) => (settings, LoadStatus::Loaded),
+        Err(e) => {
+            // Keep the unreadable file around rather than overwriting it on the next save.
+            let _ = fs::rename(&path, path.with_extension("corrupt.json"));
+            (Settings::default(), LoadStatus::Corrupt(e.to_string()))
+        }
+    }
+}
+
+pub fn save(app: &AppHandle, settings: &Settings) -> std::io::Result<()> {
+    let path = settings_path(app)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "app config dir unavailable"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(settings)?;
+    fs::write(path, json)
+}
+
+// Snapshot of the managed settings.
+pub fn current(app: &AppHandle) -> Settings {
+    app.state::<Mutex<Settings>>().lock().unwrap().clone()
+}
+
+// Applies `change` to the managed settings and writes the result to disk.
+pub fn update(app: &AppHandle, change: impl FnOnce(&mut Settings)) -> std::io::Result<Settings> {
+    let state = app.state::<Mutex<Settings>>();
+    let mut settings = state.lock().unwrap();
+    change(&mut settings);
+    save(app, &settings)?;
+    Ok(settings.clone())
+}
+
+// `incoming`'s preferences on top of the bookkeeping the app keeps for itself,
+// which callers replacing the settings wholesale mustn't overwrite.
+fn with_preferences(current: &Settings, incoming: Settings) -> Settings {
+    Settings {
+        server_recheck_hours: current.server_recheck_hours,
+        last_update_check: current.last_update_check,
+        skipped_update: current.skipped_update.clone(),
+        last_seen_whatsnew: current.last_seen_whatsnew.clone(),
+        ..incoming
+    }
+}
+
+// Re-applies the persisted settings through the same setters the individual
+// commands use, after they were replaced wholesale. User agent, logging and
+// audit levels are read at launch and take effect on restart.
+#[cfg(desktop)]
+fn apply(app: &AppHandle) {
+    let settings = current(app);
+    crate::theme::set(app, settings.theme);
+    if let Err(e) = crate::autostart::set(app, settings.launch_at_login) {
+        log::warn!("Failed to apply launch at login: {}", e);
+    }
+    crate::shortcuts::reload(app);
+    if let Err(e) = crate::windows::set_menu_visibility(app, settings.menu_visible) {
+        log::warn!("Failed to apply menu visibility: {}", e);
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        if let Err(e) = crate::zoom::set(&window, settings.zoom) {
+            log::warn!("Failed to apply zoom level: {}", e);
+        }
+        let window = window.as_ref().window();
+        if let Err(e) = crate::windows::set_keep_on_top(&window, settings.always_on_top) {
+            log::warn!("Failed to apply keep-on-top: {}", e);
+        }
+        if let Err(e) = crate::windows::set_window_decorations(&window, settings.decorations) {
+            log::warn!("Failed to apply window decorations: {}", e);
+        }
+        crate::windows::reapply_opacity(&window);
+    }
+    crate::updater::clear_cached_update(app);
+}
+
+#[tauri::command]
+pub fn set_update_channel(app: AppHandle, channel: String) -> Result<(), String> {
+    let channel = match channel.as_str() {
+        "stable" => UpdateChannel::Stable,
+        "beta" => UpdateChannel::Beta,
+        other => return Err(format!("Unknown update channel: {}", other)),
+    };
+    let previous = current(&app).update_channel;
+    update(&app, |settings| settings.update_channel = channel).map_err(|e| e.to_string())?;
+    #[cfg(desktop)]
+    crate::updater::clear_cached_update(&app);
+
+    // The updater never offers an older version, so a beta build stays put on stable.
+    let on_prerelease = !app.package_info().version.pre.is_empty();
+    if previous == UpdateChannel::Beta && channel == UpdateChannel::Stable && on_prerelease {
+        app.dialog()
+            .message("You're running a beta build that is newer than the latest stable release. Moving back to stable may require a clean reinstall.")
+            .kind(MessageDialogKind::Warning)
+            .title("Switching to Stable")
+            .show(|_| {});
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_settings(app: AppHandle) -> Settings {
+    current(&app)
+}
+
+// Replaces the user's preferences; fields the app tracks itself are kept.
+#[tauri::command]
+pub fn set_settings(app: AppHandle, settings: Settings) -> Result<(), String> {
+    update(&app, |current| *current = with_preferences(current, settings)).map_err(|e| e.to_string())?;
+    #[cfg(desktop)]
+    apply(&app);
+    Ok(())
+}
+
+// On-disk layout of an exported settings file.
+#[cfg(desktop)]
+#[derive(Serialize, Deserialize)]
+struct SettingsExport {
+    schema_version: u32,
+    app_version: String,
+    settings: Settings,
+}
+
+// Validates an exported file before anything is overwritten.
+#[cfg(desktop)]
+fn parse_export(contents: &str) -> Result<Settings, CommandError> {
+    let invalid = |message: String| CommandError::new(ErrorCode::InvalidInput, message);
+    let value: serde_json::Value =
+        serde_json::from_str(contents).map_err(|e| invalid(format!("Not a valid settings file: {}", e)))?;
+    let Some(version) = value.get("schema_version").and_then(|v| v.as_u64()) else {
+        return Err(invalid("Not an Angular Momentum settings export.".into()));
+    };
+    if version > u64::from(SCHEMA_VERSION) {
+        let exported_by = value.get("app_version").and_then(|v| v.as_str()).unwrap_or("a newer version");
+        return Err(CommandError::new(ErrorCode::UnsupportedVersion, format!(
+            "These settings were exported by Angular Momentum {} and can't be imported into this version. Update the app and try again.",
+            exported_by
+        )));
+    }
+    let export: SettingsExport = serde_json::from_value(value)
+        .map_err(|e| invalid(format!("Settings file doesn't match the expected format: {}", e)))?;
+    Ok(export.settings)
+}
+
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn export_settings(app: AppHandle) -> Result<(), CommandError> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Export Settings")
+        .set_file_name("angular-momentum-settings.json")
+        .add_filter("JSON", &["json"])
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+    let Some(path) = rx.await.ok().flatten() else { return Ok(()) };
+    let path = path.into_path().map_err(|e| CommandError::new(ErrorCode::InvalidInput, e.to_string()))?;
+
+    let export = SettingsExport {
+        schema_version: SCHEMA_VERSION,
+        app_version: app.package_info().version.to_string(),
+        settings: current(&app),
+    };
+    let json = serde_json::to_string_pretty(&export).map_err(|e| CommandError::new(ErrorCode::Internal, e.to_string()))?;
+    fs::write(&path, json)?;
+    log::info!("Exported settings to {}", path.display());
+    Ok(())
+}
+
+// Returns the imported settings, or `None` if the user cancelled the picker.
+// The settings being replaced are kept in `settings.backup.json`. An import never
+// changes the update URL, so a shared file can't redirect where updates come from.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn import_settings(app: AppHandle) -> Result<Option<Settings>, CommandError> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Import Settings")
+        .add_filter("JSON", &["json"])
+        .pick_file(move |path| {
+            let _ = tx.send(path);
+        });
+    let Some(path) = rx.await.ok().flatten() else { return Ok(None) };
+    let path = path.into_path().map_err(|e| CommandError::new(ErrorCode::InvalidInput, e.to_string()))?;
+
+    let contents = fs::read_to_string(&path)?;
+    let imported = parse_export(&contents)?;
+
+    if let Some(existing) = settings_path(&app).filter(|path| path.exists()) {
+        fs::copy(&existing, existing.with_extension("backup.json"))
+            .map_err(|e| CommandError::io(&e, format!("Couldn't back up current settings: {}", e)))?;
+    }
+    update(&app, |current| {
+        let update_url = current.update_url.clone();
+        *current = Settings { update_url, ..with_preferences(current, imported) };
+    })?;
+    apply(&app);
+    log::info!("Imported settings from {}", path.display());
+    Ok(Some(current(&app)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{with_preferences, Settings, ThemePreference};
+
+    #[test]
+    fn replacing_settings_keeps_bookkeeping() {
+        let current = Settings {
+            last_update_check: Some(100),
+            server_recheck_hours: Some(6),
+            skipped_update: Some("1.2.0".into()),
+            last_seen_whatsnew: Some("1.1.0".into()),
+            ..Settings::default()
+        };
+        let incoming = Settings {
+            theme: ThemePreference::Dark,
+            last_update_check: Some(0),
+            server_recheck_hours: None,
+            last_seen_whatsnew: Some("9.9.9".into()),
+            ..Settings::default()
+        };
+        let merged = with_preferences(&current, incoming);
+        assert_eq!(merged.theme, ThemePreference::Dark);
+        assert_eq!(merged.last_update_check, Some(100));
+        assert_eq!(merged.server_recheck_hours, Some(6));
+        assert_eq!(merged.skipped_update.as_deref(), Some("1.2.0"));
+        assert_eq!(merged.last_seen_whatsnew.as_deref(), Some("1.1.0"));
+    }
+}
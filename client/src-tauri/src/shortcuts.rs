@@ -0,0 +1,51 @@
+use tauri::{App, AppHandle};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::{settings, tray, updater};
+
+// Registers `accelerator` to run `action` on key press. A shortcut that fails to
+// parse or is already claimed by another app is logged and skipped.
+fn register_shortcut(app: &AppHandle, name: &str, accelerator: &str, action: fn(&AppHandle)) {
+    let shortcut = match accelerator.parse::<Shortcut>() {
+        Ok(shortcut) => shortcut,
+        Err(e) => {
+            log::warn!("Ignoring invalid {} shortcut {:?}: {}", name, accelerator, e);
+            return;
+        }
+    };
+    let result = app.global_shortcut().on_shortcut(shortcut, move |app, _, event| {
+        if event.state() == ShortcutState::Pressed {
+            action(app);
+        }
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to register {} shortcut {:?}: {}", name, accelerator, e);
+    }
+}
+
+// Registers the global shortcuts configured in settings; `None` disables one.
+pub fn register(app: &App) -> tauri::Result<()> {
+    app.handle().plugin(tauri_plugin_global_shortcut::Builder::new().build())?;
+    register_configured(app.handle());
+    Ok(())
+}
+
+// Swaps the registered shortcuts for the ones now in settings.
+pub fn reload(app: &AppHandle) {
+    if let Err(e) = app.global_shortcut().unregister_all() {
+        log::warn!("Failed to unregister global shortcuts: {}", e);
+    }
+    register_configured(app);
+}
+
+fn register_configured(app: &AppHandle) {
+    let settings = settings::current(app);
+    if let Some(accelerator) = &settings.update_shortcut {
+        register_shortcut(app, "update", accelerator, |app| {
+            tauri::async_runtime::spawn(updater::check_for_updates(app.clone(), false));
+        });
+    }
+    if let Some(accelerator) = &settings.toggle_window_shortcut {
+        register_shortcut(app, "show/hide window", accelerator, tray::toggle_main_window);
+    }
+}
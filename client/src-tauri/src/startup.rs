@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{App, AppHandle, Manager, WebviewWindowBuilder};
+
+// The main window starts hidden (see tauri.conf.json) and is shown once the
+// frontend has painted, so there's no white flash. This is the longest it stays
+// hidden if `frontend_ready` never arrives.
+const READY_TIMEOUT: Duration = Duration::from_secs(5);
+
+static FRONTEND_READY: AtomicBool = AtomicBool::new(false);
+
+// tauri.conf.json marks the main window `"create": false` so it can be built here,
+// where the app version is known for the desktop user agent.
+pub fn create_main_window(app: &App) -> tauri::Result<()> {
+    let Some(config) = app.config().app.windows.iter().find(|window| window.label == "main") else {
+        log::error!("No main window in tauri.conf.json");
+        return Ok(());
+    };
+    let builder = WebviewWindowBuilder::from_config(app.handle(), config)?;
+    #[cfg(desktop)]
+    let user_agent = crate::user_agent::for_app(app.handle());
+    #[cfg(desktop)]
+    let builder = builder.user_agent(&user_agent);
+    builder.build()?;
+    #[cfg(desktop)]
+    crate::user_agent::record(app.handle(), user_agent);
+    Ok(())
+}
+
+// Safety net for a frontend that fails to load or never calls `frontend_ready`.
+pub fn spawn_ready_timeout(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(READY_TIMEOUT).await;
+        if FRONTEND_READY.load(Ordering::Relaxed) {
+            return;
+        }
+        log::warn!("Frontend didn't report ready within {:?}; showing the window anyway", READY_TIMEOUT);
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+        }
+    });
+}
+
+// Called by the frontend once it has mounted.
+#[tauri::command]
+pub fn frontend_ready(window: tauri::Window) {
+    if window.label() == "main" {
+        FRONTEND_READY.store(true, Ordering::Relaxed);
+    }
+    if let Err(e) = window.show() {
+        log::warn!("Failed to show window {}: {}", window.label(), e);
+    }
+}
@@ -0,0 +1,78 @@
+use tauri::{AppHandle, Emitter, Manager, Theme};
+
+use crate::{events, menu};
+use crate::settings::{self, ThemePreference};
+
+fn to_window_theme(preference: ThemePreference) -> Option<Theme> {
+    match preference {
+        ThemePreference::System => None,
+        ThemePreference::Light => Some(Theme::Light),
+        ThemePreference::Dark => Some(Theme::Dark),
+    }
+}
+
+pub fn menu_id(preference: ThemePreference) -> &'static str {
+    match preference {
+        ThemePreference::System => "theme_system",
+        ThemePreference::Light => "theme_light",
+        ThemePreference::Dark => "theme_dark",
+    }
+}
+
+pub fn from_menu_id(id: &str) -> Option<ThemePreference> {
+    match id {
+        "theme_system" => Some(ThemePreference::System),
+        "theme_light" => Some(ThemePreference::Light),
+        "theme_dark" => Some(ThemePreference::Dark),
+        _ => None,
+    }
+}
+
+fn emit_changed(app: &AppHandle, preference: ThemePreference, theme: Option<Theme>) {
+    let _ = app.emit(events::THEME_CHANGED, events::ThemeChanged { preference, theme });
+}
+
+// Applies the preference to every open window without persisting it.
+fn apply(app: &AppHandle, preference: ThemePreference) {
+    for window in app.webview_windows().values() {
+        if let Err(e) = window.set_theme(to_window_theme(preference)) {
+            log::warn!("Failed to set theme on window {}: {}", window.label(), e);
+        }
+    }
+}
+
+pub fn set(app: &AppHandle, preference: ThemePreference) {
+    if let Err(e) = settings::update(app, |settings| settings.theme = preference) {
+        log::warn!("Failed to persist theme: {}", e);
+    }
+    apply(app, preference);
+    sync_menu(app, preference);
+
+    let theme = app.get_webview_window("main").and_then(|window| window.theme().ok());
+    emit_changed(app, preference, theme);
+}
+
+// The Theme submenu behaves like a radio group.
+fn sync_menu(app: &AppHandle, preference: ThemePreference) {
+    for option in [ThemePreference::System, ThemePreference::Light, ThemePreference::Dark] {
+        if let Some(item) = menu::check_item(app, menu_id(option)) {
+            let _ = item.set_checked(option == preference);
+        }
+    }
+}
+
+// Called during setup, before the frontend paints, so there's no flash of the wrong theme.
+pub fn restore(app: &AppHandle) {
+    apply(app, settings::current(app).theme);
+}
+
+// Re-emits OS appearance changes while following the system theme.
+// `system://theme-changed` carries just the new OS appearance ("light"/"dark").
+// Platforms that don't report appearance changes simply never send it.
+pub fn os_theme_changed(app: &AppHandle, theme: Theme) {
+    let preference = settings::current(app).theme;
+    if preference == ThemePreference::System {
+        emit_changed(app, preference, Some(theme));
+        let _ = app.emit(events::SYSTEM_THEME_CHANGED, events::SystemThemeChanged(theme));
+    }
+}
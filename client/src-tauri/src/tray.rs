@@ -0,0 +1,82 @@
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{App, AppHandle, Manager};
+
+use crate::settings;
+
+// Tray menu events are delivered to the app-wide `on_menu_event` handler.
+pub fn create(app: &App) -> tauri::Result<()> {
+    let show = MenuItem::with_id(app, "tray_show", "Show Angular Momentum", true, None::<&str>)?;
+    let check_updates = MenuItem::with_id(app, "tray_check_updates", "Check for Updates...", true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let quit = MenuItem::with_id(app, "tray_quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&show, &check_updates, &separator, &quit])?;
+
+    let mut tray = TrayIconBuilder::with_id("main")
+        .tooltip("Angular Momentum")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                toggle_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+    Ok(())
+}
+
+// Whether closing the main window should hide it instead. `minimize_to_tray` always
+// hides; otherwise it hides when the app keeps running with no windows open, as
+// macOS apps conventionally do (the Dock or tray brings the window back). Quit from
+// the app menu, the tray, or Cmd+Q always exits.
+pub fn hides_on_close(app: &AppHandle) -> bool {
+    let settings = settings::current(app);
+    hides_main_on_close(settings.minimize_to_tray, settings.quit_on_last_window_close)
+}
+
+fn hides_main_on_close(minimize_to_tray: bool, quit_on_last_window_close: bool) -> bool {
+    minimize_to_tray || !quit_on_last_window_close
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+pub fn toggle_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_visible().unwrap_or(false) {
+            let _ = window.hide();
+        } else {
+            show_main_window(app);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hides_main_on_close;
+
+    #[test]
+    fn tray_overrides_quitting() {
+        assert!(hides_main_on_close(true, true));
+        assert!(hides_main_on_close(true, false));
+    }
+
+    #[test]
+    fn follows_quit_on_last_window_close() {
+        assert!(hides_main_on_close(false, false));
+        assert!(!hides_main_on_close(false, true));
+    }
+}
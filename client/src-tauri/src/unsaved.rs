@@ -0,0 +1,42 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager, State};
+#[cfg(desktop)]
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+// Set by the frontend while it holds work that hasn't been saved yet.
+#[derive(Default)]
+pub struct UnsavedChanges(AtomicBool);
+
+pub fn is_dirty(app: &AppHandle) -> bool {
+    app.state::<UnsavedChanges>().0.load(Ordering::Relaxed)
+}
+
+// Forgets pending changes so the next close or quit goes straight through.
+pub fn discard(app: &AppHandle) {
+    app.state::<UnsavedChanges>().0.store(false, Ordering::Relaxed);
+}
+
+// Asks before throwing away unsaved work; `proceed` runs only if the user agrees.
+#[cfg(desktop)]
+pub fn confirm_discard<F>(app: &AppHandle, proceed: F)
+where
+    F: FnOnce(&AppHandle) + Send + 'static,
+{
+    let app_handle = app.clone();
+    app.dialog()
+        .message("You have unsaved changes. Quit anyway?")
+        .title("Unsaved Changes")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom("Quit".into(), "Cancel".into()))
+        .show(move |quit| {
+            if quit {
+                discard(&app_handle);
+                proceed(&app_handle);
+            }
+        });
+}
+
+#[tauri::command]
+pub fn set_dirty(dirty: bool, state: State<UnsavedChanges>) {
+    state.0.store(dirty, Ordering::Relaxed);
+}
@@ -0,0 +1,1850 @@
+use base64::Engine;
+use futures_util::StreamExt;
+use std::future::Future;
+use reqwest::header::{HeaderValue, ACCEPT, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{App, AppHandle, Emitter, Manager, Runtime, State};
+use tauri_plugin_dialog::{DialogExt, MessageDialogBuilder, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_updater::{Update, Updater, UpdaterExt};
+use tokio::sync::{oneshot, watch};
+
+use crate::error::{CommandError, ErrorCode};
+use crate::events::DownloadProgress;
+use crate::{badge, events, feature_flags, idle, network, notifications, progress, settings, unsaved, windows};
+
+// Minimum gap between progress notifications while the main window is hidden.
+const PROGRESS_NOTIFICATION_INTERVAL: Duration = Duration::from_secs(5);
+// How often the main window's visibility is re-read during a download.
+const VISIBILITY_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+// Routes download progress: `event` while the main window is visible, throttled
+// OS notifications while it's hidden (e.g. in the tray). The app icon shows it either way.
+struct ProgressReporter {
+    app: AppHandle,
+    event: &'static str,
+    visible: bool,
+    checked_at: Option<Instant>,
+    notified_at: Option<Instant>,
+    // Last percentage put on the app icon; `Some(None)` while the size is unknown.
+    icon_percent: Option<Option<u64>>,
+}
+
+impl ProgressReporter {
+    fn new(app: AppHandle, event: &'static str) -> Self {
+        Self { app, event, visible: true, checked_at: None, notified_at: None, icon_percent: None }
+    }
+
+    fn main_window_visible(&mut self) -> bool {
+        if self.checked_at.is_none_or(|at| at.elapsed() >= VISIBILITY_CHECK_INTERVAL) {
+            self.visible = self.app
+                .get_webview_window("main")
+                .is_some_and(|window| window.is_visible().unwrap_or(true) && !window.is_minimized().unwrap_or(false));
+            self.checked_at = Some(Instant::now());
+        }
+        self.visible
+    }
+
+    // Only touches the taskbar/Dock when the whole percentage changes.
+    fn show_on_icon(&mut self, progress: &DownloadProgress) {
+        let percent = progress.total.filter(|&total| total > 0).map(|total| progress.downloaded * 100 / total);
+        if self.icon_percent == Some(percent) {
+            return;
+        }
+        self.icon_percent = Some(percent);
+        let Some(window) = self.app.get_webview_window("main") else { return };
+        match percent {
+            Some(percent) => progress::set(&window, Some(percent as f64 / 100.0)),
+            None => progress::indeterminate(&window),
+        }
+    }
+
+    fn report(&mut self, progress: DownloadProgress) {
+        self.show_on_icon(&progress);
+        if self.main_window_visible() {
+            let _ = self.app.emit(self.event, progress);
+            return;
+        }
+        if self.notified_at.is_some_and(|at| at.elapsed() < PROGRESS_NOTIFICATION_INTERVAL) {
+            return;
+        }
+        self.notified_at = Some(Instant::now());
+        let body = match progress.total {
+            Some(total) if total > 0 => format!("{}% downloaded", progress.downloaded * 100 / total),
+            _ => format!("{:.1} MB downloaded", progress.downloaded as f64 / (1024.0 * 1024.0)),
+        };
+        let _ = self.app.notification()
+            .builder()
+            .title("Downloading Update")
+            .body(body)
+            .show();
+    }
+}
+
+// Delay before the first background check, so startup isn't competing with the network.
+const BACKGROUND_STARTUP_DELAY: Duration = Duration::from_secs(5);
+// How often the background loop wakes to see whether a check is due.
+const BACKGROUND_POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// Max characters of release notes shown in the update dialog.
+const RELEASE_NOTES_MAX_CHARS: usize = 600;
+
+// Flattens release-notes markdown into plain text for a message dialog:
+// drops heading markers, emphasis and code ticks, turns list markers into bullets,
+// keeps link text, and truncates with an ellipsis.
+fn format_release_notes(body: &str) -> String {
+    let mut lines = Vec::new();
+    for line in body.lines() {
+        let trimmed = line.trim();
+        let text = trimmed.trim_start_matches('#').trim_start();
+        let text = match text.strip_prefix("- ").or_else(|| text.strip_prefix("* ")) {
+            Some(item) => format!("• {}", item),
+            None => text.to_string(),
+        };
+        lines.push(strip_inline_markdown(&text));
+    }
+    let notes = lines.join("\n").trim().to_string();
+
+    if notes.chars().count() > RELEASE_NOTES_MAX_CHARS {
+        let truncated: String = notes.chars().take(RELEASE_NOTES_MAX_CHARS).collect();
+        format!("{}…", truncated.trim_end())
+    } else {
+        notes
+    }
+}
+
+// Removes `**`, `__`, and backticks, and rewrites `[text](url)` as `text`.
+fn strip_inline_markdown(text: &str) -> String {
+    let text = text.replace("**", "").replace("__", "").replace('`', "");
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some(open) = rest.find('[') {
+        let Some(close) = rest[open..].find("](").map(|i| open + i) else { break };
+        let Some(end) = rest[close..].find(')').map(|i| close + i) else { break };
+        out.push_str(&rest[..open]);
+        out.push_str(&rest[open + 1..close]);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    UpToDate,
+    Installed,
+    Declined,
+    Cancelled,
+}
+
+// Returned by `update_status`, for rendering an update badge without any dialog.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum UpdateStatus {
+    UpToDate,
+    Available { version: String, notes: Option<String> },
+    Error { message: String },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateError {
+    // The updater plugin failed to initialize, so this build can't update itself.
+    #[error("updates are disabled in this build")]
+    Disabled,
+    // The updater couldn't be built for the configured channel.
+    #[error("updater not available: {0}")]
+    Unavailable(String),
+    #[error("network error: {message}")]
+    Network { message: String, offline: bool },
+    #[error("not enough disk space: need {needed} bytes, {available} available")]
+    DiskSpace { needed: u64, available: u64 },
+    // The package didn't match its signature: corrupted in transit or tampered with.
+    #[error("update signature verification failed: {0}")]
+    Signature(#[source] tauri_plugin_updater::Error),
+    // A delta patch couldn't be used; the full package is downloaded instead.
+    #[error("delta patch failed: {0}")]
+    Patch(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("update check failed: {0}")]
+    Check(#[source] tauri_plugin_updater::Error),
+    #[error("update install failed: {0}")]
+    Install(#[source] tauri_plugin_updater::Error),
+}
+
+impl UpdateError {
+    // Pulls network, disk and signature failures out of the updater's error so they
+    // can be reported the same way whichever phase they happened in.
+    fn classify(e: tauri_plugin_updater::Error) -> Result<Self, tauri_plugin_updater::Error> {
+        match e {
+            tauri_plugin_updater::Error::Reqwest(e) => Ok(UpdateError::Network {
+                offline: e.is_connect() || e.is_timeout(),
+                message: e.to_string(),
+            }),
+            tauri_plugin_updater::Error::Network(message) => Ok(UpdateError::Network { message, offline: false }),
+            tauri_plugin_updater::Error::Io(e) => Ok(UpdateError::Io(e)),
+            e @ (tauri_plugin_updater::Error::Minisign(_)
+            | tauri_plugin_updater::Error::Base64(_)
+            | tauri_plugin_updater::Error::SignatureUtf8(_)) => Ok(UpdateError::Signature(e)),
+            other => Err(other),
+        }
+    }
+
+    fn from_check(e: tauri_plugin_updater::Error) -> Self {
+        Self::classify(e).unwrap_or_else(UpdateError::Check)
+    }
+
+    fn from_install(e: tauri_plugin_updater::Error) -> Self {
+        Self::classify(e).unwrap_or_else(UpdateError::Install)
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, UpdateError::Network { .. })
+    }
+
+    // The one place update failures are worded for users.
+    pub fn user_message(&self) -> String {
+        match self {
+            UpdateError::Disabled => "Updates are not available in this build.".into(),
+            UpdateError::Unavailable(e) => format!("Updater not available: {}", e),
+            UpdateError::Network { offline: true, .. } => {
+                "You appear to be offline. Check your connection and try again.".into()
+            }
+            UpdateError::Network { message, .. } => format!("Couldn't reach the update server: {}", message),
+            UpdateError::DiskSpace { needed, available } => format!(
+                "Not enough disk space to install the update. It needs about {} MB free, but only {} MB is available.",
+                needed / (1024 * 1024),
+                available / (1024 * 1024)
+            ),
+            UpdateError::Signature(_) => format!(
+                "The downloaded update failed its security check and was not installed. This can mean the download \
+                 was corrupted or tampered with. Try again, or download the latest version from the official \
+                 releases page: {}",
+                RELEASES_URL
+            ),
+            UpdateError::Patch(e) => format!("Couldn't apply the update patch, downloading the full update instead: {}", e),
+            UpdateError::Io(e) => format!("Couldn't write the update to disk: {}", e),
+            UpdateError::Check(e) => format!("Failed to check for updates: {}", e),
+            UpdateError::Install(e) => format!("Failed to install update: {}", e),
+        }
+    }
+}
+
+// Update failures as the frontend sees them, worded by `user_message`.
+impl From<UpdateError> for CommandError {
+    fn from(e: UpdateError) -> Self {
+        let code = match &e {
+            UpdateError::Disabled | UpdateError::Unavailable(_) => ErrorCode::Unavailable,
+            UpdateError::Network { offline: true, .. } => ErrorCode::Offline,
+            UpdateError::Network { .. } | UpdateError::Check(_) | UpdateError::Install(_) | UpdateError::Patch(_) => {
+                ErrorCode::Unavailable
+            }
+            UpdateError::DiskSpace { .. } => ErrorCode::DiskFull,
+            UpdateError::Signature(_) => ErrorCode::SignatureInvalid,
+            UpdateError::Io(io) => return CommandError::io(io, e.user_message()),
+        };
+        CommandError::new(code, e.user_message())
+    }
+}
+
+// Why the updater plugin couldn't be registered, if it couldn't.
+struct InitError(Option<String>);
+
+// Registers the updater plugin and its state. A failure (e.g. a missing or malformed pubkey) is
+// recorded rather than aborting startup; update checks then report that this build
+// can't update.
+pub fn init(app: &App) {
+    let error = app.handle()
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .err()
+        .map(|e| {
+            log::warn!("Updater plugin failed to initialize; updates disabled: {}", e);
+            e.to_string()
+        });
+    app.manage(InitError(error));
+    app.manage(DownloadCancel::default());
+    app.manage(DownloadPause::default());
+    app.manage(AppState::default());
+    app.manage(RelaunchOnExit::default());
+    app.manage(HttpClient(build_client(app.handle(), "update", |builder| builder.timeout(REQUEST_TIMEOUT))));
+    app.manage(DownloadClient(build_client(app.handle(), "download", |builder| {
+        builder.connect_timeout(DOWNLOAD_CONNECT_TIMEOUT).read_timeout(DOWNLOAD_READ_TIMEOUT)
+    })));
+    #[cfg(target_os = "windows")]
+    remove_elevated_installers(app.handle());
+    app.manage(LaunchedAfterUpdate(Mutex::new(take_update_marker(app.handle()))));
+}
+
+// Written just before an update is installed, so the next launch can tell it
+// follows an update.
+const UPDATE_MARKER_FILE: &str = "update-marker.json";
+
+// The versions either side of the update this launch follows.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub previous_version: String,
+    pub current_version: String,
+}
+
+// Set at startup from the marker; taken by the first `launched_after_update` call.
+pub struct LaunchedAfterUpdate(Mutex<Option<UpdateInfo>>);
+
+fn update_marker_path(app: &AppHandle) -> Option<std::path::PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(UPDATE_MARKER_FILE))
+}
+
+fn write_update_marker(app: &AppHandle, update: &Update) {
+    let Some(path) = update_marker_path(app) else { return };
+    let info = UpdateInfo {
+        previous_version: update.current_version.clone(),
+        current_version: update.version.clone(),
+    };
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Err(e) = serde_json::to_string(&info).map_err(std::io::Error::from).and_then(|json| std::fs::write(&path, json)) {
+        log::warn!("Failed to write update marker: {}", e);
+    }
+}
+
+fn remove_update_marker(app: &AppHandle) {
+    if let Some(path) = update_marker_path(app) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+// Reads and removes the marker. It only counts if this build is the version that
+// was being installed; otherwise the install didn't take.
+fn take_update_marker(app: &AppHandle) -> Option<UpdateInfo> {
+    let path = update_marker_path(app)?;
+    let contents = std::fs::read_to_string(&path).ok()?;
+    let _ = std::fs::remove_file(&path);
+    let info: UpdateInfo = serde_json::from_str(&contents).ok()?;
+    if info.current_version != app.package_info().version.to_string() {
+        log::warn!("Update to {} didn't take; still running {}", info.current_version, app.package_info().version);
+        return None;
+    }
+    log::info!("Launched after updating from {}", info.previous_version);
+    Some(info)
+}
+
+// The single capability check for anything that talks to the update server.
+fn ensure_available(app: &AppHandle) -> Result<(), UpdateError> {
+    match app.state::<InitError>().0 {
+        Some(_) => Err(UpdateError::Disabled),
+        None => Ok(()),
+    }
+}
+
+pub fn is_available(app: &AppHandle) -> bool {
+    ensure_available(app).is_ok()
+}
+
+// Overrides the update server, e.g. to point at an internal mirror.
+const UPDATE_URL_ENV: &str = "ANGMOM_UPDATE_URL";
+
+// The plugin refuses plain http in release builds, so it's only let through in
+// debug builds, for a local test server.
+fn parse_endpoint(url: &str) -> Result<tauri::Url, String> {
+    let url = tauri::Url::parse(url).map_err(|e| e.to_string())?;
+    match url.scheme() {
+        "https" => Ok(url),
+        "http" if cfg!(debug_assertions) => Ok(url),
+        scheme => Err(format!("unsupported scheme \"{}\"", scheme)),
+    }
+}
+
+// `ANGMOM_UPDATE_URL` wins over the `update_url` setting, which wins over the
+// channel's default. A malformed override is logged and skipped.
+pub fn update_endpoint(settings: &settings::Settings) -> Result<tauri::Url, UpdateError> {
+    resolve_endpoint(std::env::var(UPDATE_URL_ENV).ok(), settings)
+}
+
+fn resolve_endpoint(env_url: Option<String>, settings: &settings::Settings) -> Result<tauri::Url, UpdateError> {
+    let overrides = [
+        (UPDATE_URL_ENV, env_url),
+        ("update_url setting", settings.update_url.clone()),
+    ];
+    for (source, url) in overrides {
+        let Some(url) = url.filter(|url| !url.trim().is_empty()) else { continue };
+        match parse_endpoint(url.trim()) {
+            Ok(url) => {
+                log::debug!("Using update endpoint {} from {}", url, source);
+                return Ok(url);
+            }
+            Err(e) => log::warn!("Ignoring malformed update URL from {} ({}): {}", source, url, e),
+        }
+    }
+    tauri::Url::parse(settings.update_channel.endpoint()).map_err(|e| UpdateError::Unavailable(e.to_string()))
+}
+
+fn build_updater(app: &AppHandle) -> Result<Updater, UpdateError> {
+    ensure_available(app)?;
+    let url = update_endpoint(&settings::current(app))?;
+    app.updater_builder()
+        .endpoints(vec![url])
+        .and_then(|builder| builder.build())
+        .map_err(|e| {
+            log::error!("Failed to build updater: {}", e);
+            UpdateError::Unavailable(e.to_string())
+        })
+}
+
+// For `plugin_health`: the plugin loaded and an updater can be built against the
+// configured endpoint. Returns the endpoint.
+pub fn health(app: &AppHandle) -> Result<tauri::Url, String> {
+    build_updater(app).map_err(|e| e.user_message())?;
+    update_endpoint(&settings::current(app)).map_err(|e| e.user_message())
+}
+
+// Shows `dialog` without blocking any thread and resolves once it's dismissed.
+// Closing the dialog without answering counts as "no".
+async fn confirm<R: Runtime>(dialog: MessageDialogBuilder<R>) -> bool {
+    let (tx, rx) = oneshot::channel();
+    dialog.show(move |confirmed| {
+        let _ = tx.send(confirmed);
+    });
+    rx.await.unwrap_or(false)
+}
+
+// Builds the prompt up front so the returned future owns everything it needs.
+fn confirm_install(app: &AppHandle, update: &Update) -> impl Future<Output = bool> {
+    let msg = match update.body.as_deref().map(format_release_notes) {
+        Some(notes) if !notes.is_empty() => format!(
+            "Version {} is available.\n\n{}\n\nWould you like to install it now?",
+            update.version, notes
+        ),
+        _ => format!("Version {} is available. Would you like to install it now?", update.version),
+    };
+    let mut dialog = app.dialog()
+        .message(msg)
+        .title("Update Available")
+        .buttons(MessageDialogButtons::OkCancel);
+    if let Some(window) = windows::focused_window(app) {
+        dialog = dialog.parent(&window);
+    }
+    let version = update.version.clone();
+    async move {
+        let confirmed = confirm(dialog).await;
+        if !confirmed {
+            log::info!("User declined update to {}", version);
+        }
+        confirmed
+    }
+}
+
+// Sender half of the cancellation channel for the download in flight, if any.
+#[derive(Default)]
+pub struct DownloadCancel(Mutex<Option<oneshot::Sender<()>>>);
+
+// Set by `pause_update`, cleared by `resume_update`; watched by the download loop.
+pub struct DownloadPause(watch::Sender<bool>);
+
+impl Default for DownloadPause {
+    fn default() -> Self {
+        Self(watch::channel(false).0)
+    }
+}
+
+// The update is held in memory and then unpacked or copied by the installer, so
+// require this multiple of its size to be free.
+const DISK_SPACE_MARGIN: u64 = 2;
+
+// Free bytes in the temp dir the installer works in.
+fn available_space(app: &AppHandle) -> Option<u64> {
+    let dir = app.path().temp_dir().unwrap_or_else(|_| std::env::temp_dir());
+    fs2::available_space(&dir)
+        .map_err(|e| log::warn!("Couldn't read free space for {}: {}", dir.display(), e))
+        .ok()
+}
+
+// 0 if the free space can't be determined.
+#[tauri::command]
+pub fn free_disk_space(app: AppHandle) -> u64 {
+    available_space(&app).unwrap_or(0)
+}
+
+// Checked as soon as the download reveals its size, before anything is buffered.
+// If the free space can't be read the download goes ahead.
+fn check_disk_space(app: &AppHandle, content_length: u64) -> Result<(), UpdateError> {
+    let needed = content_length.saturating_mul(DISK_SPACE_MARGIN);
+    let Some(available) = available_space(app) else { return Ok(()) };
+    if available < needed {
+        log::error!("Not enough disk space for update: need {} bytes, {} available", needed, available);
+        return Err(UpdateError::DiskSpace { needed, available });
+    }
+    Ok(())
+}
+
+// `Update::download` checks the package signature; since we download it ourselves
+// to support pausing, do the same check against the pubkey in tauri.conf.json.
+fn verify_signature(app: &AppHandle, data: &[u8], signature: &str) -> Result<(), UpdateError> {
+    let pubkey = app.config().plugins.0.get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|key| key.as_str())
+        .ok_or_else(|| UpdateError::Unavailable("no updater pubkey configured".into()))?;
+    let decode = |encoded: &str| -> Result<String, tauri_plugin_updater::Error> {
+        let bytes = base64::engine::general_purpose::STANDARD.decode(encoded)?;
+        String::from_utf8(bytes).map_err(|_| tauri_plugin_updater::Error::SignatureUtf8(encoded.into()))
+    };
+    let verify = || -> Result<(), tauri_plugin_updater::Error> {
+        let key = minisign_verify::PublicKey::decode(&decode(pubkey)?)?;
+        let signature = minisign_verify::Signature::decode(&decode(signature)?)?;
+        key.verify(data, &signature, true)?;
+        Ok(())
+    };
+    verify().map_err(|e| {
+        log::error!("SECURITY: update package failed signature verification and was discarded: {}", e);
+        UpdateError::Signature(e)
+    })
+}
+
+// A connection that can't be opened, or goes quiet mid-download, fails instead of
+// hanging the update forever. There's no overall limit, since a large package on a
+// slow connection can legitimately take a long time.
+const DOWNLOAD_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const DOWNLOAD_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Used by `fetch_package` for packages and patches.
+struct DownloadClient(reqwest::Client);
+
+// What identifies this exact file for `If-Range`: a strong ETag, else Last-Modified.
+fn resume_validator(response: &reqwest::Response) -> Option<HeaderValue> {
+    let headers = response.headers();
+    headers
+        .get(ETAG)
+        .filter(|etag| !etag.as_bytes().starts_with(b"W/"))
+        .or_else(|| headers.get(LAST_MODIFIED))
+        .cloned()
+}
+
+// Downloads `url` (the package or a patch), stopping while `pause_update` is in
+// effect. A resume asks for the rest with a Range request; a server that ignores it
+// sends the whole file and the download starts over.
+async fn fetch_package(app: &AppHandle, url: &tauri::Url, reporter: &mut ProgressReporter) -> Result<Vec<u8>, UpdateError> {
+    let request_error = |e: reqwest::Error| UpdateError::from_install(e.into());
+    let client = app.state::<DownloadClient>().0.clone();
+    let mut paused = app.state::<DownloadPause>().0.subscribe();
+    let mut buffer = Vec::new();
+    let mut validator = None;
+    let mut checked_space = false;
+    loop {
+        let mut request = client.get(url.clone()).header(ACCEPT, "application/octet-stream");
+        if !buffer.is_empty() {
+            match &validator {
+                // The server sends the whole file instead of a range if it has changed,
+                // so new bytes are never spliced onto old ones.
+                Some(validator) => {
+                    request = request.header(RANGE, format!("bytes={}-", buffer.len())).header(IF_RANGE, validator);
+                }
+                None => {
+                    log::info!("Update server gave no ETag or Last-Modified; restarting the download");
+                    buffer.clear();
+                }
+            }
+        }
+        let response = request.send().await.and_then(|r| r.error_for_status()).map_err(request_error)?;
+        if !buffer.is_empty() && response.status() != StatusCode::PARTIAL_CONTENT {
+            log::info!("Update server didn't resume (unsupported, or the file changed); restarting the download");
+            buffer.clear();
+        }
+        if buffer.is_empty() {
+            validator = resume_validator(&response);
+        }
+        let total = response.content_length().map(|length| length + buffer.len() as u64);
+        if let (false, Some(total)) = (checked_space, total) {
+            check_disk_space(app, total)?;
+            checked_space = true;
+        }
+
+        let mut stream = response.bytes_stream();
+        let finished = loop {
+            tokio::select! {
+                chunk = stream.next() => match chunk {
+                    Some(chunk) => {
+                        buffer.extend_from_slice(&chunk.map_err(request_error)?);
+                        reporter.report(DownloadProgress {
+                            downloaded: buffer.len() as u64,
+                            total,
+                            indeterminate: total.is_none(),
+                        });
+                    }
+                    None => break true,
+                },
+                Ok(_) = paused.wait_for(|paused| *paused) => break false,
+            }
+        };
+        if finished {
+            return Ok(buffer);
+        }
+
+        // Drop the connection rather than leave it stalled for the length of the pause.
+        drop(stream);
+        log::info!("Update download paused at {} bytes", buffer.len());
+        let _ = app.emit(events::UPDATE_PAUSED, events::DownloadOffset(buffer.len() as u64));
+        let _ = paused.wait_for(|paused| !*paused).await;
+        log::info!("Resuming update download from {} bytes", buffer.len());
+        let _ = app.emit(events::UPDATE_RESUMED, events::DownloadOffset(buffer.len() as u64));
+    }
+}
+
+// The last installed package is kept here as the base that delta patches apply to.
+const PATCH_BASE_DIR: &str = "update-base";
+
+fn patch_base_path(app: &AppHandle, version: &str) -> Option<std::path::PathBuf> {
+    app.path().app_cache_dir().ok().map(|dir| dir.join(PATCH_BASE_DIR).join(version))
+}
+
+// Keeps `bytes` as the base for the next update's patch, pruning bases for any
+// version other than this one and the one running now.
+fn store_patch_base(app: &AppHandle, update: &Update, bytes: &[u8]) {
+    let Some(path) = patch_base_path(app, &update.version) else { return };
+    let Some(dir) = path.parent() else { return };
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            if name != update.version.as_str() && name != update.current_version.as_str() {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+    let result = std::fs::create_dir_all(dir).and_then(|()| std::fs::write(&path, bytes));
+    if let Err(e) = result {
+        log::warn!("Couldn't keep update package as a patch base: {}", e);
+    }
+}
+
+fn remove_patch_base(app: &AppHandle, version: &str) {
+    if let Some(path) = patch_base_path(app, version) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+// A manifest can offer bsdiff patches from earlier versions next to the full package:
+// `platforms.<target>.patches.<from version>.url`. The patched result must be the
+// full package byte for byte, so it's checked against the package's own signature.
+fn advertised_patch(raw_json: &serde_json::Value, target: &str, current_version: &str) -> Option<tauri::Url> {
+    let url = raw_json.get("platforms")?.get(target)?.get("patches")?.get(current_version)?.get("url")?.as_str()?;
+    tauri::Url::parse(url)
+        .map_err(|e| log::warn!("Ignoring malformed patch URL {}: {}", url, e))
+        .ok()
+}
+
+fn apply_patch(base: &[u8], patch: &[u8]) -> Result<Vec<u8>, String> {
+    let mut patched = Vec::new();
+    bsdiff::patch(base, &mut &patch[..], &mut patched).map_err(|e| e.to_string())?;
+    Ok(patched)
+}
+
+// Fetches the patch and rebuilds the package from the kept base. Any failure here
+// sends the caller to the full download.
+async fn fetch_via_patch(app: &AppHandle, update: &Update, url: &tauri::Url, reporter: &mut ProgressReporter) -> Result<Vec<u8>, UpdateError> {
+    let base_path = patch_base_path(app, &update.current_version)
+        .ok_or_else(|| UpdateError::Patch("app cache dir unavailable".into()))?;
+    let base = std::fs::read(&base_path)
+        .map_err(|e| UpdateError::Patch(format!("no patch base for {}: {}", update.current_version, e)))?;
+    let patch = fetch_package(app, url, reporter).await?;
+    let _ = app.emit(events::UPDATE_PATCHING, ());
+    let patched = tauri::async_runtime::spawn_blocking(move || apply_patch(&base, &patch))
+        .await
+        .map_err(|e| UpdateError::Patch(e.to_string()))?
+        .map_err(|e| UpdateError::Patch(format!("patch didn't apply: {}", e)))?;
+    verify_signature(app, &patched, &update.signature)?;
+    Ok(patched)
+}
+
+// Awaits `patch` if there is one and falls back to `full` when it fails for any
+// reason, telling `on_patch_failed` why. Returns whether the bytes came from the patch.
+async fn patch_or_full<P, F>(
+    patch: Option<P>,
+    full: impl FnOnce() -> F,
+    on_patch_failed: impl FnOnce(&UpdateError),
+) -> Result<(Vec<u8>, bool), UpdateError>
+where
+    P: Future<Output = Result<Vec<u8>, UpdateError>>,
+    F: Future<Output = Result<Vec<u8>, UpdateError>>,
+{
+    if let Some(patch) = patch {
+        match patch.await {
+            Ok(bytes) => return Ok((bytes, true)),
+            Err(e) => on_patch_failed(&e),
+        }
+    }
+    full().await.map(|bytes| (bytes, false))
+}
+
+// Prefers a delta patch when the manifest has one for the running version, falling
+// back to the full package if there's no patch base or the patch fails in any way.
+// Either way the bytes returned have passed signature verification.
+async fn fetch_verified(app: &AppHandle, update: &Update) -> Result<Vec<u8>, UpdateError> {
+    let patch_url = advertised_patch(&update.raw_json, &update.target, &update.current_version);
+    let mut patch_reporter = ProgressReporter::new(app.clone(), events::UPDATE_PATCH_PROGRESS);
+    let patch = patch_url.as_ref().map(|url| fetch_via_patch(app, update, url, &mut patch_reporter));
+    let full = || async {
+        let mut reporter = ProgressReporter::new(app.clone(), events::UPDATE_DOWNLOAD_PROGRESS);
+        let bytes = fetch_package(app, &update.download_url, &mut reporter).await?;
+        verify_signature(app, &bytes, &update.signature)?;
+        Ok::<_, UpdateError>(bytes)
+    };
+    let on_patch_failed = |e: &UpdateError| {
+        log::warn!("Delta update failed, downloading the full package: {}", e);
+        let _ = app.emit(events::UPDATE_PATCH_FAILED, events::PatchFailed(e.user_message()));
+    };
+    let (bytes, patched) = patch_or_full(patch, full, on_patch_failed).await?;
+    if patched {
+        log::info!("Updated {} to {} with a delta patch", update.current_version, update.version);
+    }
+    Ok(bytes)
+}
+
+// Downloads the update into memory, returning `None` if `cancel_update` interrupts it.
+// Nothing touches disk until `install`, so a cancelled download leaves no partial file.
+async fn download(app: &AppHandle, update: &Update) -> Result<Option<Vec<u8>>, UpdateError> {
+    let (cancel_tx, cancel_rx) = oneshot::channel();
+    *app.state::<DownloadCancel>().0.lock().unwrap() = Some(cancel_tx);
+    app.state::<DownloadPause>().0.send_replace(false);
+
+    let result = tokio::select! {
+        bytes = fetch_verified(app, update) => bytes.map(Some).map_err(|e| {
+            log::error!("Failed to download update {}: {}", update.version, e);
+            e
+        }),
+        Ok(()) = cancel_rx => {
+            log::info!("Update download cancelled");
+            let _ = app.emit(events::UPDATE_CANCELLED, ());
+            Ok(None)
+        }
+    };
+    app.state::<DownloadCancel>().0.lock().unwrap().take();
+    if let Some(window) = app.get_webview_window("main") {
+        progress::set(&window, None);
+    }
+
+    let bytes = result?;
+    if bytes.is_some() {
+        let _ = app.emit(events::UPDATE_DOWNLOAD_FINISHED, ());
+    }
+    Ok(bytes)
+}
+
+// Aborts the download in flight. Returns whether there was one to cancel.
+#[tauri::command]
+pub fn cancel_update(pending: State<'_, DownloadCancel>) -> bool {
+    match pending.0.lock().unwrap().take() {
+        Some(cancel) => cancel.send(()).is_ok(),
+        None => false,
+    }
+}
+
+fn is_downloading(app: &AppHandle) -> bool {
+    app.state::<DownloadCancel>().0.lock().unwrap().is_some()
+}
+
+// Suspends the download in flight. Returns whether it was running.
+#[tauri::command]
+pub fn pause_update(app: AppHandle) -> bool {
+    is_downloading(&app) && !app.state::<DownloadPause>().0.send_replace(true)
+}
+
+// Continues a paused download. Returns whether it was paused.
+#[tauri::command]
+pub fn resume_update(app: AppHandle) -> bool {
+    is_downloading(&app) && app.state::<DownloadPause>().0.send_replace(false)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// Transient network failures are retried after each of these delays in turn;
+// anything else (e.g. a malformed manifest) fails immediately.
+const CHECK_RETRY_DELAYS: [Duration; 3] = [Duration::from_secs(1), Duration::from_secs(2), Duration::from_secs(4)];
+
+async fn check_with_retry(updater: &Updater) -> Result<Option<Update>, UpdateError> {
+    let mut delays = CHECK_RETRY_DELAYS.iter();
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        log::debug!("Update check attempt {}", attempt);
+        let error = match updater.check().await {
+            Ok(update) => return Ok(update),
+            Err(e) => UpdateError::from_check(e),
+        };
+        match delays.next() {
+            Some(delay) if error.is_retryable() => {
+                log::warn!("Update check attempt {} failed, retrying in {:?}: {}", attempt, delay, error);
+                tokio::time::sleep(*delay).await;
+            }
+            _ => return Err(error),
+        }
+    }
+}
+
+// Repeat checks within this window reuse the previous result instead of hitting the server.
+const CHECK_DEBOUNCE: Duration = Duration::from_secs(5 * 60);
+
+// The last successful check and the endpoint it asked, for debouncing.
+struct CachedCheck {
+    checked_at: Instant,
+    endpoint: tauri::Url,
+    update: Option<Update>,
+}
+
+#[derive(Default)]
+pub struct AppState {
+    last_check: Mutex<Option<CachedCheck>>,
+}
+
+// Only reused while fresh and for the endpoint currently configured, so switching
+// channel or update URL never answers with the old server's result.
+fn cached_update(app: &AppHandle) -> Option<Option<Update>> {
+    let endpoint = update_endpoint(&settings::current(app)).ok()?;
+    let state = app.state::<AppState>();
+    let last_check = state.last_check.lock().unwrap();
+    let cached = last_check.as_ref().filter(|cached| cached.endpoint == endpoint)?;
+    if cached.checked_at.elapsed() >= CHECK_DEBOUNCE {
+        return None;
+    }
+    log::debug!("Reusing update check from {:?} ago", cached.checked_at.elapsed());
+    Some(cached.update.clone())
+}
+
+fn cache_update(app: &AppHandle, update: &Option<Update>) {
+    let Ok(endpoint) = update_endpoint(&settings::current(app)) else { return };
+    *app.state::<AppState>().last_check.lock().unwrap() =
+        Some(CachedCheck { checked_at: Instant::now(), endpoint, update: update.clone() });
+}
+
+// Forgets the last check, e.g. once the settings it was made under change.
+pub fn clear_cached_update(app: &AppHandle) {
+    if let Some(state) = app.try_state::<AppState>() {
+        state.last_check.lock().unwrap().take();
+    }
+}
+
+// Queries the configured channel, recording the time of every successful check.
+// Unless `force` is set, a check made within `CHECK_DEBOUNCE` is reused.
+async fn fetch_update(app: &AppHandle, force: bool) -> Result<Option<Update>, UpdateError> {
+    if !force {
+        if let Some(update) = cached_update(app) {
+            return Ok(update);
+        }
+    }
+    let channel = settings::current(app).update_channel;
+    log::debug!("Checking for updates on the {:?} channel", channel);
+    let updater = build_updater(app)?;
+    let skipped = settings::current(app).skipped_update;
+    let update = match check_with_retry(&updater).await {
+        Ok(Some(update)) if skipped.as_deref() == Some(update.version.as_str()) => {
+            log::info!("Not offering {}; it was rolled back", update.version);
+            None
+        }
+        Ok(Some(update)) => {
+            log::info!("Update available: {} -> {}", update.current_version, update.version);
+            Some(update)
+        }
+        Ok(None) => {
+            log::info!("No update available; running the latest version");
+            None
+        }
+        Err(e) => {
+            log::error!("Update check failed: {}", e);
+            return Err(e);
+        }
+    };
+    cache_update(app, &update);
+    badge::set(app, update.as_ref().map(|_| 1));
+    if let Some(update) = &update {
+        feature_flags::apply_manifest(app, &update.raw_json);
+    }
+    // The plugin only hands back the manifest when there's an update in it, so an
+    // up-to-date check reads it separately; if that fails the last interval stands.
+    let recheck_hours = match &update {
+        Some(update) => Some(recheck_hours(&update.raw_json)),
+        None => fetch_manifest(app).await.map(|manifest| recheck_hours(&manifest)),
+    };
+    let _ = settings::update(app, |settings| {
+        settings.last_update_check = Some(unix_now());
+        if let Some(hours) = recheck_hours {
+            settings.server_recheck_hours = hours;
+        }
+    });
+    Ok(update)
+}
+
+async fn prompt_and_install(app: &AppHandle, update: &Update) -> Result<UpdateOutcome, UpdateError> {
+    if !confirm_install(app, update).await {
+        return Ok(UpdateOutcome::Declined);
+    }
+    install(app, update).await
+}
+
+// The branches of an interactive check, with the server, the prompt and the
+// installer passed in so each can be tested on its own.
+async fn check_and_prompt<U, A, I>(
+    check: impl Future<Output = Result<Option<U>, UpdateError>>,
+    confirm: impl FnOnce(&U) -> A,
+    install: impl FnOnce(U) -> I,
+) -> Result<UpdateOutcome, UpdateError>
+where
+    A: Future<Output = bool>,
+    I: Future<Output = Result<UpdateOutcome, UpdateError>>,
+{
+    let Some(update) = check.await? else { return Ok(UpdateOutcome::UpToDate) };
+    if !confirm(&update).await {
+        return Ok(UpdateOutcome::Declined);
+    }
+    install(update).await
+}
+
+async fn install(app: &AppHandle, update: &Update) -> Result<UpdateOutcome, UpdateError> {
+    let bytes = match download(app, update).await? {
+        Some(bytes) => bytes,
+        None => return Ok(UpdateOutcome::Cancelled),
+    };
+    // Some platforms exit from inside `install`, so the marker and patch base have to
+    // be written first.
+    write_update_marker(app, update);
+    store_patch_base(app, update, &bytes);
+    // Kept in case the installer has to be started again with elevation.
+    #[cfg(target_os = "windows")]
+    let installer = bytes.clone();
+    if let Err(e) = update.install(bytes) {
+        let error = UpdateError::from_install(e);
+        #[cfg(target_os = "windows")]
+        match launch_failure(&error) {
+            Some(LaunchFailure::UacDeclined) => {
+                remove_update_marker(app);
+                remove_patch_base(app, &update.version);
+                log::info!("User declined the UAC prompt for {}", update.version);
+                return Ok(UpdateOutcome::Declined);
+            }
+            Some(LaunchFailure::AccessDenied) => return install_elevated(app, update, &installer).await,
+            None => {}
+        }
+        remove_update_marker(app);
+        remove_patch_base(app, &update.version);
+        log::error!("Failed to install update {}: {}", update.version, error);
+        return Err(error);
+    }
+    log::info!("Installed update {}", update.version);
+    // The cached update is the one just installed.
+    clear_cached_update(app);
+    Ok(UpdateOutcome::Installed)
+}
+
+// What came back from the plugin's ShellExecute call, which starts the installer.
+// ShellExecute brings up UAC itself when the installer's manifest asks for it, so
+// a failure here is either a declined prompt or an installer that was refused
+// the rights it needs (a per-machine install started from a limited account).
+#[cfg(target_os = "windows")]
+enum LaunchFailure {
+    UacDeclined,
+    AccessDenied,
+}
+
+#[cfg(target_os = "windows")]
+fn launch_failure(error: &UpdateError) -> Option<LaunchFailure> {
+    use windows_sys::Win32::Foundation::{ERROR_ACCESS_DENIED, ERROR_CANCELLED, ERROR_ELEVATION_REQUIRED};
+    let UpdateError::Io(e) = error else { return None };
+    match e.raw_os_error().map(|code| code as u32) {
+        Some(ERROR_CANCELLED) => Some(LaunchFailure::UacDeclined),
+        Some(ERROR_ACCESS_DENIED | ERROR_ELEVATION_REQUIRED) => Some(LaunchFailure::AccessDenied),
+        _ => None,
+    }
+}
+
+// Where an elevated installer is written. It has to outlive this process, so it's
+// removed on the next launch by `remove_elevated_installers`.
+#[cfg(target_os = "windows")]
+fn elevated_installer_dir(app: &AppHandle) -> Option<std::path::PathBuf> {
+    app.path().app_cache_dir().ok().map(|dir| dir.join("elevated-installer"))
+}
+
+#[cfg(target_os = "windows")]
+fn remove_elevated_installers(app: &AppHandle) {
+    if let Some(dir) = elevated_installer_dir(app).filter(|dir| dir.exists()) {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            log::warn!("Failed to remove leftover installer in {}: {}", dir.display(), e);
+        }
+    }
+}
+
+// The arguments the plugin would have given the installer for the configured
+// `installMode` (default passive), plus any configured `installerArgs`, so an
+// elevated run behaves like a normal one.
+#[cfg(target_os = "windows")]
+fn installer_args(app: &AppHandle, is_msi: bool, path: &std::path::Path) -> String {
+    let windows = app.config().plugins.0.get("updater").and_then(|updater| updater.get("windows"));
+    let install_mode = windows.and_then(|windows| windows.get("installMode")).and_then(|mode| mode.as_str());
+    let mut args: Vec<String> = match (is_msi, install_mode.unwrap_or("passive")) {
+        (true, mode) => {
+            let ui = match mode {
+                "quiet" => "/quiet",
+                "basicUi" => "/qb+",
+                _ => "/passive",
+            };
+            vec!["/i".into(), format!("\"{}\"", path.display()), ui.into(), "/promptrestart".into(), "AUTOLAUNCHAPP=True".into()]
+        }
+        (false, "quiet") => vec!["/S".into(), "/R".into()],
+        (false, "basicUi") => vec![],
+        (false, _) => vec!["/P".into(), "/R".into()],
+    };
+    if !is_msi {
+        args.push("/UPDATE".into());
+    }
+    let configured = windows.and_then(|windows| windows.get("installerArgs")).and_then(|args| args.as_array());
+    args.extend(configured.into_iter().flatten().filter_map(|arg| arg.as_str().map(str::to_string)));
+    args.join(" ")
+}
+
+// Explains the UAC prompt, then starts the installer with the "runas" verb and
+// quits so it can replace the app. Declining (either dialog) leaves the current
+// version untouched: the installer never runs and its copy is deleted.
+#[cfg(target_os = "windows")]
+async fn install_elevated(app: &AppHandle, update: &Update, installer: &[u8]) -> Result<UpdateOutcome, UpdateError> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::ERROR_CANCELLED;
+    use windows_sys::Win32::UI::Shell::ShellExecuteW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let abandon = |app: &AppHandle| {
+        remove_update_marker(app);
+        remove_patch_base(app, &update.version);
+        remove_elevated_installers(app);
+    };
+    let proceed = confirm(
+        app.dialog()
+            .message(format!(
+                "Installing version {} needs administrator permission. Windows will ask you to allow the installer, \
+                 and Angular Momentum will close while it runs.",
+                update.version
+            ))
+            .title("Administrator Permission Needed")
+            .kind(MessageDialogKind::Info)
+            .buttons(MessageDialogButtons::OkCancelCustom("Continue".into(), "Not Now".into())),
+    )
+    .await;
+    if !proceed {
+        abandon(app);
+        log::info!("User declined elevated install of {}", update.version);
+        return Ok(UpdateOutcome::Declined);
+    }
+
+    let is_msi = update.download_url.path().to_ascii_lowercase().ends_with(".msi");
+    let dir = elevated_installer_dir(app)
+        .ok_or_else(|| UpdateError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, "app cache dir unavailable")))?;
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("angular-momentum-{}-setup.{}", update.version, if is_msi { "msi" } else { "exe" }));
+    std::fs::write(&path, installer)?;
+    let parameters = installer_args(app, is_msi, &path);
+    let program = if is_msi { std::ffi::OsString::from("msiexec.exe") } else { path.as_os_str().to_owned() };
+    let wide = |value: &std::ffi::OsStr| value.encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+    let (verb, program, parameters) = (
+        wide(std::ffi::OsStr::new("runas")),
+        wide(program.as_os_str()),
+        wide(std::ffi::OsStr::new(&parameters)),
+    );
+    // SAFETY: every pointer is a NUL-terminated UTF-16 buffer that outlives the call.
+    let result = unsafe {
+        ShellExecuteW(
+            std::ptr::null_mut(),
+            verb.as_ptr(),
+            program.as_ptr(),
+            parameters.as_ptr(),
+            std::ptr::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    // ShellExecuteW reports success with a value above 32.
+    if result as usize <= 32 {
+        let error = std::io::Error::last_os_error();
+        abandon(app);
+        if error.raw_os_error() == Some(ERROR_CANCELLED as i32) {
+            log::info!("User declined the UAC prompt for {}", update.version);
+            return Ok(UpdateOutcome::Declined);
+        }
+        log::error!("Failed to start elevated installer for {}: {}", update.version, error);
+        return Err(UpdateError::Io(error));
+    }
+    log::info!("Started elevated installer for {}", update.version);
+    app.exit(0);
+    Ok(UpdateOutcome::Installed)
+}
+
+// Where users are sent to download a build by hand.
+const RELEASES_URL: &str = "https://github.com/TheGameKnave/angular-momentum/releases/latest";
+
+// Manifest of a single tagged release on GitHub, for reinstalling a specific version.
+const RELEASE_ENDPOINT_TEMPLATE: &str =
+    "https://github.com/TheGameKnave/angular-momentum/releases/download/v{version}/latest.json";
+
+// Installs exactly `version`, even when it's older than the running build. With
+// the channel defaults that's the tagged release's manifest; an `update_url` or
+// `ANGMOM_UPDATE_URL` server is asked instead, and has to be offering `version`.
+pub async fn reinstall_version(app: &AppHandle, version: &str) -> Result<UpdateOutcome, UpdateError> {
+    log::info!("Reinstalling version {}", version);
+    ensure_available(app)?;
+    let settings = settings::current(app);
+    let endpoint = update_endpoint(&settings)?;
+    let url = if endpoint.as_str() == settings.update_channel.endpoint() {
+        tauri::Url::parse(&RELEASE_ENDPOINT_TEMPLATE.replace("{version}", version))
+            .map_err(|e| UpdateError::Unavailable(e.to_string()))?
+    } else {
+        endpoint
+    };
+    let wanted = version.to_string();
+    let updater = app.updater_builder()
+        .version_comparator(move |_, release| release.version.to_string() == wanted)
+        .endpoints(vec![url])
+        .and_then(|builder| builder.build())
+        .map_err(|e| UpdateError::Unavailable(e.to_string()))?;
+    match check_with_retry(&updater).await? {
+        Some(update) => install(app, &update).await,
+        None => Err(UpdateError::Unavailable(format!("the update server isn't offering version {}", version))),
+    }
+}
+
+// Checks the configured channel and, if the user confirms, installs the update.
+// Result dialogs are left to `show_outcome` so the flow can be driven without them.
+pub async fn run_update_check(app: &AppHandle, force: bool) -> Result<UpdateOutcome, UpdateError> {
+    check_and_prompt(
+        fetch_update(app, force),
+        |update| confirm_install(app, update),
+        |update| async move { install(app, &update).await },
+    )
+    .await
+}
+
+// Peeks at the update this launch follows without taking it from the frontend.
+pub fn after_update(app: &AppHandle) -> Option<UpdateInfo> {
+    app.state::<LaunchedAfterUpdate>().0.lock().unwrap().clone()
+}
+
+// The versions either side of the update this launch follows, if any. Only the
+// first call returns it, so a "what's new" banner shows once.
+#[tauri::command]
+pub fn launched_after_update(state: State<'_, LaunchedAfterUpdate>) -> Option<UpdateInfo> {
+    state.0.lock().unwrap().take()
+}
+
+// Silent check: never prompts and never downloads.
+#[tauri::command]
+pub async fn update_status(app: AppHandle) -> UpdateStatus {
+    match fetch_update(&app, false).await {
+        Ok(Some(update)) => UpdateStatus::Available {
+            version: update.version.clone(),
+            notes: update.body.clone(),
+        },
+        Ok(None) => UpdateStatus::UpToDate,
+        Err(e) => UpdateStatus::Error { message: e.user_message() },
+    }
+}
+
+// Longest a small request to the update server (ping, manifest, package size)
+// waits before reporting a timeout.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Shared by those small requests: one connection pool, the app's user agent and
+// `REQUEST_TIMEOUT` on every request.
+struct HttpClient(reqwest::Client);
+
+// Every client talking to the update server sends the app's user agent; `configure`
+// adds the timeouts that suit its requests.
+fn build_client(
+    app: &AppHandle,
+    purpose: &str,
+    configure: impl FnOnce(reqwest::ClientBuilder) -> reqwest::ClientBuilder,
+) -> reqwest::Client {
+    let builder = reqwest::Client::builder().user_agent(format!("angular-momentum/{}", app.package_info().version));
+    configure(builder).build().unwrap_or_else(|e| {
+        log::warn!("Failed to build the {} HTTP client, using defaults: {}", purpose, e);
+        reqwest::Client::new()
+    })
+}
+
+fn http_client(app: &AppHandle) -> reqwest::Client {
+    app.state::<HttpClient>().0.clone()
+}
+
+// Names the likely cause from the error chain: reqwest only flags timeouts and
+// connection failures itself, so DNS and TLS problems are spotted by message.
+fn describe_ping_error(e: &reqwest::Error) -> CommandError {
+    let mut chain = Vec::new();
+    let mut source: Option<&dyn std::error::Error> = Some(e);
+    while let Some(err) = source {
+        chain.push(err.to_string().to_lowercase());
+        source = err.source();
+    }
+    let mentions = |needles: &[&str]| chain.iter().any(|message| needles.iter().any(|needle| message.contains(needle)));
+    if e.is_timeout() {
+        let message = format!("The update server didn't respond within {} seconds.", REQUEST_TIMEOUT.as_secs());
+        CommandError::new(ErrorCode::Timeout, message)
+    } else if mentions(&["dns", "resolve", "lookup"]) {
+        CommandError::new(ErrorCode::Offline, "Couldn't resolve the update server's address (DNS). Check your connection.")
+    } else if mentions(&["certificate", "tls", "ssl", "handshake"]) {
+        let message = format!("Couldn't establish a secure connection to the update server (TLS): {}", e);
+        CommandError::new(ErrorCode::Tls, message)
+    } else if e.is_connect() {
+        CommandError::new(ErrorCode::Offline, "Couldn't connect to the update server. You may be offline.")
+    } else {
+        CommandError::new(ErrorCode::Unavailable, format!("Couldn't reach the update server: {}", e))
+    }
+}
+
+// Round-trip time in milliseconds to the configured update endpoint, without
+// checking for an update. Lets the UI tell "no updates" from "can't reach server".
+#[tauri::command]
+pub async fn ping_update_server(app: AppHandle) -> Result<u64, CommandError> {
+    let url = update_endpoint(&settings::current(&app))?;
+    let client = http_client(&app);
+    let started = Instant::now();
+    let mut response = client.head(url.clone()).send().await;
+    // Some static hosts don't answer HEAD; the manifest is small enough to GET.
+    if matches!(&response, Ok(r) if r.status() == StatusCode::METHOD_NOT_ALLOWED) {
+        response = client.get(url.clone()).send().await;
+    }
+    let elapsed = started.elapsed().as_millis() as u64;
+    match response {
+        Ok(response) if response.status().is_success() => {
+            log::debug!("Update server {} responded in {} ms", url, elapsed);
+            Ok(elapsed)
+        }
+        Ok(response) => {
+            let message = format!("The update server responded with {}.", response.status());
+            Err(CommandError::new(ErrorCode::Unavailable, message))
+        }
+        Err(e) => {
+            log::warn!("Update server {} unreachable: {}", url, e);
+            Err(describe_ping_error(&e))
+        }
+    }
+}
+
+// What's known about the pending update before downloading it.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDetails {
+    pub version: String,
+    pub current_version: String,
+    // RFC 3339, as published in the manifest.
+    pub release_date: Option<String>,
+    pub download_url: String,
+    // `None` when neither the manifest nor the download server reports it.
+    pub size: Option<u64>,
+    pub target: String,
+}
+
+// Manifests don't carry a size by default; accept one per platform or at the top level.
+fn advertised_size(raw_json: &serde_json::Value, target: &str) -> Option<u64> {
+    raw_json.get("platforms")
+        .and_then(|platforms| platforms.get(target))
+        .and_then(|platform| platform.get("size"))
+        .or_else(|| raw_json.get("size"))
+        .and_then(|size| size.as_u64())
+}
+
+// Asks the download server for the package size without fetching it.
+async fn fetch_size(app: &AppHandle, url: &tauri::Url) -> Option<u64> {
+    let response = http_client(app).head(url.clone()).send().await.ok()?;
+    response.error_for_status().ok()?.content_length()
+}
+
+#[tauri::command]
+pub async fn update_details(app: AppHandle) -> Result<UpdateDetails, CommandError> {
+    let update = fetch_update(&app, false)
+        .await?
+        .ok_or_else(|| CommandError::new(ErrorCode::UpToDate, "You're running the latest version."))?;
+    let size = match advertised_size(&update.raw_json, &update.target) {
+        Some(size) => Some(size),
+        None => fetch_size(&app, &update.download_url).await,
+    };
+    Ok(UpdateDetails {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        release_date: update.raw_json.get("pub_date").and_then(|date| date.as_str()).map(str::to_string),
+        download_url: update.download_url.to_string(),
+        size,
+        target: update.target.clone(),
+    })
+}
+
+pub fn show_outcome(app: &AppHandle, result: Result<UpdateOutcome, UpdateError>) {
+    match result {
+        Ok(UpdateOutcome::UpToDate) => {
+            app.dialog()
+                .message("You're running the latest version.")
+                .title("No Updates")
+                .show(|_| {});
+        }
+        Ok(UpdateOutcome::Installed) => {
+            let restart_handle = app.clone();
+            app.dialog()
+                .message("Update installed. Restart now to finish updating?")
+                .title("Update Complete")
+                .buttons(MessageDialogButtons::OkCancelCustom("Restart Now".into(), "Later".into()))
+                .show(move |restart| {
+                    if restart {
+                        // Settings are written as they change; only buffered log lines are pending.
+                        log::logger().flush();
+                        // The user chose to restart; don't stop on the unsaved-changes prompt.
+                        unsaved::discard(&restart_handle);
+                        restart_handle.restart();
+                    }
+                });
+        }
+        Ok(UpdateOutcome::Declined | UpdateOutcome::Cancelled) => {}
+        Err(e @ UpdateError::Signature(_)) => {
+            app.dialog()
+                .message(e.user_message())
+                .kind(MessageDialogKind::Warning)
+                .title("Update Verification Failed")
+                .show(|_| {});
+        }
+        Err(e @ UpdateError::Disabled) => {
+            app.dialog()
+                .message(e.user_message())
+                .title("Updates Unavailable")
+                .show(|_| {});
+        }
+        Err(e) => {
+            app.dialog()
+                .message(e.user_message())
+                .kind(MessageDialogKind::Error)
+                .title("Update Error")
+                .show(|_| {});
+        }
+    }
+}
+
+// Entry point shared by the app menu, the tray menu, and the global shortcut.
+// Menu clicks pass `force` so an explicit request always reaches the server.
+pub async fn check_for_updates(app: AppHandle, force: bool) {
+    let result = run_update_check(&app, force).await;
+    show_outcome(&app, result);
+}
+
+pub const SILENT_UPDATE_FLAG: &str = "--silent-update";
+
+pub fn is_silent_update_requested() -> bool {
+    std::env::args().any(|arg| arg == SILENT_UPDATE_FLAG)
+}
+
+// Set by a silent update that installed something; the app comes back up once
+// this process has exited.
+#[derive(Default)]
+struct RelaunchOnExit(AtomicBool);
+
+// Called on `RunEvent::Exit`, after plugins have seen it: by then the single-instance
+// lock is released, so the new process starts normally instead of handing its
+// arguments to this one and quitting. The silent flag is dropped for the same reason.
+pub fn relaunch_if_requested(app: &AppHandle) {
+    let requested = app
+        .try_state::<RelaunchOnExit>()
+        .is_some_and(|relaunch| relaunch.0.load(Ordering::Relaxed));
+    if !requested {
+        return;
+    }
+    let result = tauri::process::current_binary(&app.env()).and_then(|binary| {
+        std::process::Command::new(binary)
+            .args(std::env::args().skip(1).filter(|arg| arg != SILENT_UPDATE_FLAG))
+            .spawn()
+    });
+    if let Err(e) = result {
+        log::error!("Failed to relaunch after silent update: {}", e);
+        log::logger().flush();
+    }
+}
+
+// Headless path for `--silent-update`: installs whatever is available without any
+// dialogs, then exits with 0 if up to date or updated and 1 on failure.
+pub async fn run_silent_update(app: AppHandle) {
+    log::info!("Running silent update");
+    let result = match fetch_update(&app, true).await {
+        Ok(Some(update)) => install(&app, &update).await,
+        Ok(None) => Ok(UpdateOutcome::UpToDate),
+        Err(e) => Err(e),
+    };
+    let code = match result {
+        Ok(UpdateOutcome::Installed) => {
+            log::info!("Silent update installed; relaunching");
+            app.state::<RelaunchOnExit>().0.store(true, Ordering::Relaxed);
+            0
+        }
+        Ok(_) => {
+            log::info!("Silent update: already up to date");
+            0
+        }
+        Err(e) => {
+            log::error!("Silent update failed: {}", e);
+            1
+        }
+    };
+    log::logger().flush();
+    app.exit(code);
+}
+
+// The user counts as mid-task while any app window has focus; a modal would interrupt them.
+fn is_user_active(app: &AppHandle) -> bool {
+    windows::focused_window(app).is_some()
+}
+
+// How soon a deferred install or an offline check is retried. A deferred install
+// only re-reads the idle time, so retrying often costs no server requests.
+const IDLE_RETRY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+// What the background loop should do next.
+enum BackgroundCheck {
+    // Nothing pending; wait for the next check to fall due.
+    Done,
+    // Offline; check again soon.
+    Retry,
+    // Found, but waiting for the machine to go idle before installing.
+    Deferred(Update),
+}
+
+// Unattended check: stays quiet unless an update is found.
+async fn background_check(app: &AppHandle) -> BackgroundCheck {
+    if !network::current(app) {
+        log::debug!("Offline; putting off the background update check");
+        return BackgroundCheck::Retry;
+    }
+    let update = match fetch_update(app, false).await {
+        Ok(Some(update)) => update,
+        Ok(None) => return BackgroundCheck::Done,
+        Err(e) => {
+            log::warn!("Background update check failed: {}", e);
+            return BackgroundCheck::Done;
+        }
+    };
+    let settings = settings::current(app);
+
+    if is_user_active(app) && settings.update_notifications && notifications::permitted(app) {
+        let _ = app.notification()
+            .builder()
+            .title("Update Available")
+            .body(format!("Version {} is available. Choose \"Check for Updates...\" to install it.", update.version))
+            .show();
+    } else if settings.update_idle_minutes > 0 {
+        return install_when_idle(app, update).await;
+    } else {
+        let result = prompt_and_install(app, &update).await;
+        if !matches!(result, Ok(UpdateOutcome::Declined | UpdateOutcome::Cancelled)) {
+            show_outcome(app, result);
+        }
+    }
+    BackgroundCheck::Done
+}
+
+// Installs without asking once the system has been idle for `update_idle_minutes`,
+// since nobody is there to answer a prompt; until then the update is handed back
+// to be tried again. Only the restart prompt is left for the user's return.
+async fn install_when_idle(app: &AppHandle, update: Update) -> BackgroundCheck {
+    let settings = settings::current(app);
+    if !idle::idle_for(Duration::from_secs(settings.update_idle_minutes.saturating_mul(60))) {
+        log::info!(
+            "Deferring update {} until the system has been idle for {} minutes",
+            update.version,
+            settings.update_idle_minutes
+        );
+        return BackgroundCheck::Deferred(update);
+    }
+    log::info!("System is idle; installing update {} unattended", update.version);
+    match install(app, &update).await {
+        Ok(UpdateOutcome::Installed) => show_outcome(app, Ok(UpdateOutcome::Installed)),
+        Ok(_) => {}
+        Err(e) => {
+            log::warn!("Unattended install of {} failed: {}", update.version, e);
+            if settings.update_notifications && notifications::permitted(app) {
+                let _ = app.notification()
+                    .builder()
+                    .title("Update Failed")
+                    .body(format!("Version {} couldn't be installed. Choose \"Check for Updates...\" to try again.", update.version))
+                    .show();
+            }
+        }
+    }
+    BackgroundCheck::Done
+}
+
+// Longest interval the server can ask for, so a bad manifest can't stop checks.
+const MAX_RECHECK_HOURS: u64 = 30 * 24;
+
+// Fetches the manifest itself, for when the plugin found nothing to hand back.
+// Endpoints with `{{...}}` placeholders are skipped; only the plugin fills those in.
+async fn fetch_manifest(app: &AppHandle) -> Option<serde_json::Value> {
+    let url = update_endpoint(&settings::current(app)).ok()?;
+    if ["{{", "%7B%7B"].iter().any(|placeholder| url.as_str().contains(placeholder)) {
+        return None;
+    }
+    let response = http_client(app).get(url).send().await.and_then(|r| r.error_for_status());
+    match response {
+        Ok(response) => serde_json::from_slice(&response.bytes().await.ok()?).ok(),
+        Err(e) => {
+            log::debug!("Couldn't fetch the update manifest for recheck_hours: {}", e);
+            None
+        }
+    }
+}
+
+// Optional `recheck_hours` in the update manifest, ignored unless it's a positive
+// integer and capped at `MAX_RECHECK_HOURS`.
+fn recheck_hours(manifest: &serde_json::Value) -> Option<u64> {
+    let hours = manifest.get("recheck_hours")?;
+    match hours.as_u64() {
+        Some(hours) if hours > 0 => Some(hours.min(MAX_RECHECK_HOURS)),
+        _ => {
+            log::warn!("Ignoring invalid recheck_hours in update manifest: {}", hours);
+            None
+        }
+    }
+}
+
+// Hours between background checks: the server's recommendation if it gave one,
+// else the configured interval.
+fn check_interval_hours(settings: &settings::Settings) -> u64 {
+    settings.server_recheck_hours.unwrap_or(settings.update_interval_hours).max(1)
+}
+
+fn log_check_interval(settings: &settings::Settings) {
+    let source = match settings.server_recheck_hours {
+        Some(_) => "from update manifest",
+        None => "configured",
+    };
+    log::info!("Background update checks every {}h ({})", check_interval_hours(settings), source);
+}
+
+// First launch only (there's no settings file yet): asks whether to check for
+// updates automatically. Either answer is saved, so the question never repeats,
+// and manual checks from the menu work regardless.
+pub fn prompt_first_run(app: &AppHandle) {
+    let app_handle = app.clone();
+    app.dialog()
+        .message("Would you like Angular Momentum to check for updates automatically? You can change this later in Preferences.")
+        .title("Automatic Updates")
+        .buttons(MessageDialogButtons::OkCancelCustom("Check Automatically".into(), "Not Now".into()))
+        .show(move |enabled| {
+            log::info!("Automatic update checks {}", if enabled { "enabled" } else { "declined" });
+            if let Err(e) = settings::update(&app_handle, |settings| settings.auto_check_updates = enabled) {
+                log::warn!("Failed to save update preference: {}", e);
+            }
+        });
+}
+
+fn is_check_due(settings: &settings::Settings) -> bool {
+    let interval = check_interval_hours(settings).saturating_mul(60 * 60);
+    match settings.last_update_check {
+        Some(last) => unix_now().saturating_sub(last) >= interval,
+        None => true,
+    }
+}
+
+// Runs one check shortly after startup, then again whenever the configured interval
+// has elapsed. Settings are re-read on every pass, so toggling the opt-in takes effect
+// without a restart.
+pub fn spawn_background_checks(app: AppHandle) {
+    if !is_available(&app) {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(BACKGROUND_STARTUP_DELAY).await;
+        let mut next = BackgroundCheck::Done;
+        if settings::current(&app).auto_check_updates {
+            next = background_check(&app).await;
+            log_check_interval(&settings::current(&app));
+        }
+        loop {
+            let delay = match next {
+                BackgroundCheck::Done => BACKGROUND_POLL_INTERVAL,
+                BackgroundCheck::Retry | BackgroundCheck::Deferred(_) => IDLE_RETRY_INTERVAL,
+            };
+            tokio::time::sleep(delay).await;
+            let settings = settings::current(&app);
+            if !settings.auto_check_updates {
+                next = BackgroundCheck::Done;
+                continue;
+            }
+            next = match next {
+                // The update already found is kept; only the idle time is re-read.
+                BackgroundCheck::Deferred(update) => install_when_idle(&app, update).await,
+                BackgroundCheck::Done if !is_check_due(&settings) => BackgroundCheck::Done,
+                BackgroundCheck::Done | BackgroundCheck::Retry => {
+                    let next = background_check(&app).await;
+                    log_check_interval(&settings::current(&app));
+                    next
+                }
+            };
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        advertised_patch, apply_patch, check_and_prompt, describe_ping_error, format_release_notes, is_check_due,
+        parse_endpoint, patch_or_full, recheck_hours, resolve_endpoint, unix_now, UpdateError, UpdateOutcome,
+        MAX_RECHECK_HOURS, RELEASE_NOTES_MAX_CHARS,
+    };
+    use crate::error::ErrorCode;
+    use std::net::TcpListener;
+    use std::time::Duration;
+    use std::cell::Cell;
+    use std::future::ready;
+    use crate::settings::{Settings, UpdateChannel};
+    use serde_json::json;
+
+    #[test]
+    fn parses_endpoints() {
+        assert!(parse_endpoint("https://updates.example.com/latest.json").is_ok());
+        assert!(parse_endpoint("ftp://updates.example.com/latest.json").is_err());
+        assert!(parse_endpoint("file:///tmp/latest.json").is_err());
+        assert!(parse_endpoint("not a url").is_err());
+        assert_eq!(parse_endpoint("http://localhost:8080/latest.json").is_ok(), cfg!(debug_assertions));
+    }
+
+    #[test]
+    fn env_url_beats_setting_beats_channel() {
+        let settings = Settings { update_url: Some("https://mirror.example.com/latest.json".into()), ..Settings::default() };
+        let env = Some("https://env.example.com/latest.json".to_string());
+        assert_eq!(resolve_endpoint(env, &settings).unwrap().as_str(), "https://env.example.com/latest.json");
+        assert_eq!(resolve_endpoint(None, &settings).unwrap().as_str(), "https://mirror.example.com/latest.json");
+        let settings = Settings { update_channel: UpdateChannel::Beta, ..Settings::default() };
+        assert_eq!(resolve_endpoint(None, &settings).unwrap().as_str(), UpdateChannel::Beta.endpoint());
+    }
+
+    #[test]
+    fn malformed_override_falls_back() {
+        let settings = Settings { update_url: Some("ftp://mirror.example.com".into()), ..Settings::default() };
+        let env = Some("   ".to_string());
+        assert_eq!(resolve_endpoint(env, &settings).unwrap().as_str(), UpdateChannel::Stable.endpoint());
+    }
+
+    #[test]
+    fn reads_recheck_hours() {
+        assert_eq!(recheck_hours(&json!({ "recheck_hours": 6 })), Some(6));
+        assert_eq!(recheck_hours(&json!({ "version": "1.0.0" })), None);
+    }
+
+    #[test]
+    fn ignores_invalid_recheck_hours() {
+        assert_eq!(recheck_hours(&json!({ "recheck_hours": 0 })), None);
+        assert_eq!(recheck_hours(&json!({ "recheck_hours": -3 })), None);
+        assert_eq!(recheck_hours(&json!({ "recheck_hours": "12" })), None);
+        assert_eq!(recheck_hours(&json!({ "recheck_hours": 1.5 })), None);
+    }
+
+    #[test]
+    fn caps_recheck_hours() {
+        assert_eq!(recheck_hours(&json!({ "recheck_hours": u64::MAX })), Some(MAX_RECHECK_HOURS));
+    }
+
+    #[test]
+    fn huge_interval_does_not_overflow() {
+        let settings = Settings { server_recheck_hours: Some(u64::MAX), last_update_check: Some(unix_now()), ..Settings::default() };
+        assert!(!is_check_due(&settings));
+        let settings = Settings { server_recheck_hours: Some(u64::MAX), last_update_check: None, ..Settings::default() };
+        assert!(is_check_due(&settings));
+    }
+
+    #[test]
+    fn finds_patch_for_running_version() {
+        let manifest = json!({
+            "platforms": {
+                "linux-x86_64": {
+                    "url": "https://example.com/full.tar.gz",
+                    "patches": { "1.2.0": { "url": "https://example.com/1.2.0.patch" } }
+                }
+            }
+        });
+        let url = advertised_patch(&manifest, "linux-x86_64", "1.2.0").unwrap();
+        assert_eq!(url.as_str(), "https://example.com/1.2.0.patch");
+        assert!(advertised_patch(&manifest, "linux-x86_64", "1.1.0").is_none());
+        assert!(advertised_patch(&manifest, "windows-x86_64", "1.2.0").is_none());
+    }
+
+    #[test]
+    fn manifest_without_patches_uses_full_download() {
+        let manifest = json!({ "platforms": { "linux-x86_64": { "url": "https://example.com/full.tar.gz" } } });
+        assert!(advertised_patch(&manifest, "linux-x86_64", "1.2.0").is_none());
+    }
+
+    #[test]
+    fn patch_rebuilds_new_package() {
+        let old = b"angular momentum 1.2.0 package contents".repeat(64);
+        let new = b"angular momentum 1.3.0 package contents, now larger".repeat(64);
+        let mut patch = Vec::new();
+        bsdiff::diff(&old, &new, &mut patch).unwrap();
+        assert_eq!(apply_patch(&old, &patch).unwrap(), new);
+    }
+
+    #[test]
+    fn truncated_patch_falls_back() {
+        let old = b"angular momentum 1.2.0".repeat(64);
+        let new = b"angular momentum 1.3.0".repeat(64);
+        let mut patch = Vec::new();
+        bsdiff::diff(&old, &new, &mut patch).unwrap();
+        patch.truncate(patch.len() / 2);
+        assert!(apply_patch(&old, &patch).is_err());
+    }
+
+    // Runs `patch_or_full` with a full download that always succeeds, returning
+    // the result and the patch failure reported, if any.
+    fn fetch(patch: Option<Result<Vec<u8>, UpdateError>>) -> (Result<(Vec<u8>, bool), UpdateError>, Option<String>) {
+        let mut failure = None;
+        let result = tauri::async_runtime::block_on(patch_or_full(
+            patch.map(ready),
+            || ready(Ok(b"full".to_vec())),
+            |e| failure = Some(e.to_string()),
+        ));
+        (result, failure)
+    }
+
+    #[test]
+    fn uses_a_patch_that_applies() {
+        let (result, failure) = fetch(Some(Ok(b"patched".to_vec())));
+        assert_eq!(result.unwrap(), (b"patched".to_vec(), true));
+        assert_eq!(failure, None);
+    }
+
+    #[test]
+    fn downloads_the_full_package_without_a_patch() {
+        let (result, failure) = fetch(None);
+        assert_eq!(result.unwrap(), (b"full".to_vec(), false));
+        assert_eq!(failure, None);
+    }
+
+    #[test]
+    fn falls_back_when_the_patch_fails() {
+        let (result, failure) = fetch(Some(Err(UpdateError::Patch("no patch base".into()))));
+        assert_eq!(result.unwrap(), (b"full".to_vec(), false));
+        assert!(failure.unwrap().contains("no patch base"));
+    }
+
+    #[test]
+    fn falls_back_when_the_patched_package_fails_verification() {
+        let mismatch = UpdateError::Signature(tauri_plugin_updater::Error::SignatureUtf8("bad".into()));
+        let (result, failure) = fetch(Some(Err(mismatch)));
+        assert_eq!(result.unwrap(), (b"full".to_vec(), false));
+        assert!(failure.unwrap().starts_with("update signature verification failed"));
+    }
+
+    #[test]
+    fn full_download_errors_are_returned() {
+        let result = tauri::async_runtime::block_on(patch_or_full(
+            Some(ready(Err(UpdateError::Patch("bad patch".into())))),
+            || ready(Err(UpdateError::Patch("also failed".into()))),
+            |_| {},
+        ));
+        assert!(matches!(result, Err(UpdateError::Patch(message)) if message == "also failed"));
+    }
+
+    // Drives `check_and_prompt` with `check` and `answer`, returning the outcome
+    // and whether the prompt and the installer were reached.
+    fn prompt(check: Result<Option<&str>, UpdateError>, answer: bool) -> (Result<UpdateOutcome, UpdateError>, bool, bool) {
+        let asked = Cell::new(false);
+        let installed = Cell::new(false);
+        let result = tauri::async_runtime::block_on(check_and_prompt(
+            ready(check),
+            |_| {
+                asked.set(true);
+                ready(answer)
+            },
+            |_| {
+                installed.set(true);
+                ready(Ok(UpdateOutcome::Installed))
+            },
+        ));
+        (result, asked.get(), installed.get())
+    }
+
+    #[test]
+    fn up_to_date_skips_the_prompt() {
+        let (result, asked, installed) = prompt(Ok(None), true);
+        assert_eq!(result.unwrap(), UpdateOutcome::UpToDate);
+        assert!(!asked && !installed);
+    }
+
+    #[test]
+    fn declining_doesnt_install() {
+        let (result, asked, installed) = prompt(Ok(Some("2.0.0")), false);
+        assert_eq!(result.unwrap(), UpdateOutcome::Declined);
+        assert!(asked && !installed);
+    }
+
+    #[test]
+    fn accepting_installs() {
+        let (result, asked, installed) = prompt(Ok(Some("2.0.0")), true);
+        assert_eq!(result.unwrap(), UpdateOutcome::Installed);
+        assert!(asked && installed);
+    }
+
+    #[test]
+    fn check_errors_skip_the_prompt() {
+        let (result, asked, installed) = prompt(Err(UpdateError::Disabled), true);
+        assert!(matches!(result, Err(UpdateError::Disabled)));
+        assert!(!asked && !installed);
+    }
+
+    fn get_error(url: &str) -> reqwest::Error {
+        let client = reqwest::Client::builder().timeout(Duration::from_millis(200)).build().unwrap();
+        tauri::async_runtime::block_on(client.get(url).send()).unwrap_err()
+    }
+
+    #[test]
+    fn ping_timeouts_are_reported_as_timeouts() {
+        // Accepted by the OS backlog but never answered.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let error = describe_ping_error(&get_error(&url));
+        assert_eq!(error.code, ErrorCode::Timeout);
+    }
+
+    #[test]
+    fn refused_connections_are_reported_as_offline() {
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let error = describe_ping_error(&get_error(&format!("http://127.0.0.1:{}/", port)));
+        assert_eq!(error.code, ErrorCode::Offline);
+        assert!(error.message.starts_with("Couldn't connect"));
+    }
+
+    #[test]
+    fn empty_release_notes_stay_empty() {
+        assert_eq!(format_release_notes(""), "");
+        assert_eq!(format_release_notes("  \n\n  "), "");
+    }
+
+    #[test]
+    fn release_notes_markdown_is_flattened() {
+        let body = "## What's new\n\n- **Faster** startup\n* Fixed `zoom` reset\nSee [the changelog](https://example.com/changelog) for __more__.";
+        assert_eq!(
+            format_release_notes(body),
+            "What's new\n\n• Faster startup\n• Fixed zoom reset\nSee the changelog for more."
+        );
+    }
+
+    #[test]
+    fn unclosed_links_are_left_alone() {
+        assert_eq!(format_release_notes("[not a link"), "[not a link");
+    }
+
+    #[test]
+    fn long_release_notes_are_truncated_on_characters() {
+        let body = "é".repeat(RELEASE_NOTES_MAX_CHARS + 50);
+        let notes = format_release_notes(&body);
+        assert!(notes.ends_with('…'));
+        assert_eq!(notes.chars().count(), RELEASE_NOTES_MAX_CHARS + 1);
+    }
+
+    #[test]
+    fn notes_at_the_limit_are_kept_whole() {
+        let body = "a".repeat(RELEASE_NOTES_MAX_CHARS);
+        assert_eq!(format_release_notes(&body), body);
+    }
+}
@@ -0,0 +1,57 @@
+use tauri::{AppHandle, Manager};
+
+use crate::settings;
+
+// The UA the main window was created with. The webview can't change it later, so
+// this is what it's sending even after `user_agent_suffix` changes.
+struct AppliedUserAgent(String);
+
+// The webview's own UA. None of the platforms expose it before a webview exists,
+// so it's rebuilt here in each engine's format.
+#[cfg(target_os = "windows")]
+fn native() -> String {
+    // WebView2 sends Chromium's reduced UA, which keeps only the major version.
+    let major = tauri::webview_version()
+        .ok()
+        .and_then(|version| version.split('.').next().map(str::to_string))
+        .unwrap_or_else(|| "0".into());
+    format!(
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{0}.0.0.0 Safari/537.36 Edg/{0}.0.0.0",
+        major
+    )
+}
+
+// WebKit froze this string: WKWebView sends it on every Mac, Apple silicon included.
+#[cfg(target_os = "macos")]
+fn native() -> String {
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko)".into()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn native() -> String {
+    format!("Mozilla/5.0 (X11; Linux {}) AppleWebKit/605.1.15 (KHTML, like Gecko)", std::env::consts::ARCH)
+}
+
+// The native UA plus `AngularMomentum/<version>` and the `user_agent_suffix` setting, if any.
+pub fn for_app(app: &AppHandle) -> String {
+    let mut user_agent = format!("{} AngularMomentum/{}", native(), app.package_info().version);
+    if let Some(suffix) = settings::current(app).user_agent_suffix.filter(|suffix| !suffix.trim().is_empty()) {
+        user_agent.push(' ');
+        user_agent.push_str(suffix.trim());
+    }
+    user_agent
+}
+
+// Called once the main window is built with `user_agent`.
+pub fn record(app: &AppHandle, user_agent: String) {
+    app.manage(AppliedUserAgent(user_agent));
+}
+
+// The UA is only applied when a window is created: changing it at runtime means
+// recreating the webview on some platforms (WebView2 in particular), so a changed
+// `user_agent_suffix` takes effect on the next launch. `None` before the main
+// window exists.
+#[tauri::command]
+pub fn user_agent(app: AppHandle) -> Option<String> {
+    app.try_state::<AppliedUserAgent>().map(|applied| applied.0.clone())
+}
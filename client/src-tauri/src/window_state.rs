@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewWindow, Window};
+
+const STATE_FILE: &str = "window-state.json";
+
+// Saved geometry of the main window. Size and position are the pre-maximize
+// bounds, so a maximized window restores maximized on top of sensible bounds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowState {
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub maximized: bool,
+}
+
+fn state_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(STATE_FILE))
+}
+
+fn load(app: &AppHandle) -> Option<WindowState> {
+    let contents = fs::read_to_string(state_path(app)?).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+// For quits that never close the main window (Cmd+Q, tray Quit, `app.exit`, restart).
+pub fn save_main(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        save(&window.as_ref().window());
+    }
+}
+
+pub fn save(window: &Window) {
+    let app = window.app_handle();
+    let Some(path) = state_path(app) else { return };
+    let maximized = window.is_maximized().unwrap_or(false);
+
+    let state = match load(app) {
+        // Keep the previous bounds; the current ones are the maximized size.
+        Some(previous) if maximized => WindowState { maximized, ..previous },
+        _ => {
+            let (Ok(size), Ok(position)) = (window.inner_size(), window.outer_position()) else { return };
+            WindowState {
+                width: size.width,
+                height: size.height,
+                x: position.x,
+                y: position.y,
+                maximized,
+            }
+        }
+    };
+
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(&state) {
+        let _ = fs::write(path, json);
+    }
+}
+
+// Whether the saved top-left corner lands on any currently connected monitor.
+fn is_on_screen(window: &WebviewWindow, state: &WindowState) -> bool {
+    window
+        .available_monitors()
+        .map(|monitors| {
+            monitors.iter().any(|monitor| {
+                let pos = monitor.position();
+                let size = monitor.size();
+                state.x >= pos.x
+                    && state.x < pos.x + size.width as i32
+                    && state.y >= pos.y
+                    && state.y < pos.y + size.height as i32
+            })
+        })
+        .unwrap_or(false)
+}
+
+pub fn restore(window: &WebviewWindow) {
+    let Some(state) = load(window.app_handle()) else { return };
+
+    let _ = window.unmaximize();
+    if is_on_screen(window, &state) {
+        let _ = window.set_size(PhysicalSize::new(state.width, state.height));
+        let _ = window.set_position(PhysicalPosition::new(state.x, state.y));
+    } else if let Ok(Some(primary)) = window.primary_monitor() {
+        // The saved monitor is gone; clamp onto the primary display.
+        let bounds = primary.size();
+        let _ = window.set_size(PhysicalSize::new(
+            state.width.min(bounds.width),
+            state.height.min(bounds.height),
+        ));
+        let _ = window.center();
+    }
+    if state.maximized {
+        let _ = window.maximize();
+    }
+}
+
+pub fn reset(window: &WebviewWindow) {
+    if let Some(path) = state_path(window.app_handle()) {
+        let _ = fs::remove_file(path);
+    }
+    // Back to the defaults from tauri.conf.json.
+    let _ = window.center();
+    let _ = window.maximize();
+}
@@ -0,0 +1,610 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, Url, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+    Window,
+};
+
+use crate::{events, menu, settings, tray, updater, window_state};
+
+pub const PREFERENCES_LABEL: &str = "preferences";
+// Additional app windows are labelled `window-1`, `window-2`, ...; capabilities match `window-*`.
+const APP_WINDOW_PREFIX: &str = "window-";
+
+static NEXT_WINDOW_ID: AtomicUsize = AtomicUsize::new(1);
+
+// Focuses the preferences window, creating it on first use so only one ever exists.
+pub fn open_preferences(app: &AppHandle) -> tauri::Result<()> {
+    if let Some(window) = app.get_webview_window(PREFERENCES_LABEL) {
+        window.show()?;
+        window.unminimize()?;
+        return window.set_focus();
+    }
+    let window = WebviewWindowBuilder::new(app, PREFERENCES_LABEL, WebviewUrl::App("preferences".into()))
+        .title("Preferences")
+        .inner_size(640.0, 480.0)
+        .resizable(false)
+        .build()?;
+    restore_menu_visibility(&window.as_ref().window());
+    menu::refresh_window_list(app, None);
+    Ok(())
+}
+
+pub const WHATS_NEW_LABEL: &str = "whats-new";
+pub const WHATS_NEW_MENU_ID: &str = "whats_new";
+
+// Focuses the What's New window for the running version, creating it if needed,
+// and records that version as seen.
+pub fn open_whats_new(app: &AppHandle) -> tauri::Result<()> {
+    let version = app.package_info().version.to_string();
+    if let Some(window) = app.get_webview_window(WHATS_NEW_LABEL) {
+        window.show()?;
+        window.unminimize()?;
+        return window.set_focus();
+    }
+    let url = WebviewUrl::App(format!("whats-new?version={}", version).into());
+    let window = WebviewWindowBuilder::new(app, WHATS_NEW_LABEL, url)
+        .title("What's New")
+        .inner_size(520.0, 600.0)
+        .build()?;
+    restore_menu_visibility(&window.as_ref().window());
+    menu::refresh_window_list(app, None);
+    if let Err(e) = settings::update(app, |settings| settings.last_seen_whatsnew = Some(version)) {
+        log::warn!("Failed to record What's New as seen: {}", e);
+    }
+    Ok(())
+}
+
+// Opens What's New once after a relaunch that follows an update. Normal launches,
+// and launches after its window has been seen for this version, leave it closed.
+pub fn show_whats_new_after_update(app: &AppHandle) {
+    if updater::after_update(app).is_none() {
+        return;
+    }
+    let version = app.package_info().version.to_string();
+    if settings::current(app).last_seen_whatsnew.as_deref() == Some(version.as_str()) {
+        return;
+    }
+    if let Err(e) = open_whats_new(app) {
+        log::error!("Failed to open What's New window: {}", e);
+    }
+}
+
+// Opens another full app window. The app-wide menu set in `menu::create` is applied
+// to every window, so on Windows/Linux new windows get the File menu too.
+pub fn open_new_window(app: &AppHandle) -> tauri::Result<WebviewWindow> {
+    let label = format!("{}{}", APP_WINDOW_PREFIX, NEXT_WINDOW_ID.fetch_add(1, Ordering::Relaxed));
+    let window = WebviewWindowBuilder::new(app, label, WebviewUrl::default())
+        .title("Angular Momentum")
+        .inner_size(800.0, 600.0)
+        .build()?;
+    restore_menu_visibility(&window.as_ref().window());
+    menu::refresh_window_list(app, None);
+    Ok(window)
+}
+
+// In-app browser windows are `browser-1`, `browser-2`, ...; no capability matches
+// them, and `audit::dispatch` rejects app commands they send.
+pub const BROWSER_WINDOW_PREFIX: &str = "browser-";
+pub const BACK_TO_APP_MENU_ID: &str = "back_to_app";
+
+// Opens `url` in its own window instead of the system browser. Only https is
+// allowed, since the page runs inside the app.
+pub fn open_in_app_window(app: &AppHandle, url: &str, title: &str) -> Result<WebviewWindow, String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    if url.scheme() != "https" || url.host_str().is_none() {
+        return Err(format!("Only https links can be opened in the app: {}", url));
+    }
+    let label = format!("{}{}", BROWSER_WINDOW_PREFIX, NEXT_WINDOW_ID.fetch_add(1, Ordering::Relaxed));
+    let builder = WebviewWindowBuilder::new(app, label, WebviewUrl::External(url))
+        .title(title)
+        .inner_size(1000.0, 700.0);
+    // A window menu would replace the global menu bar on macOS; there Cmd+W closes it.
+    #[cfg(not(target_os = "macos"))]
+    let builder = builder.menu(menu::browser_menu(app).map_err(|e| e.to_string())?);
+    let window = builder.build().map_err(|e| e.to_string())?;
+    menu::refresh_window_list(app, None);
+    Ok(window)
+}
+
+// "Back to App" in an in-app browser window: closes it and returns to main.
+pub fn back_to_app(app: &AppHandle) {
+    if let Some(window) = focused_window(app).filter(|window| window.label().starts_with(BROWSER_WINDOW_PREFIX)) {
+        let _ = window.close();
+    }
+    tray::show_main_window(app);
+}
+
+// Async because building a window from a sync command deadlocks on Windows.
+#[tauri::command]
+pub async fn open_in_app(app: AppHandle, url: String, title: String) -> Result<(), String> {
+    open_in_app_window(&app, &url, &title).map(|_| ())
+}
+
+// The window the user is working in, if any has focus; app-level flows such as
+// the updater attach their dialogs to it.
+pub fn focused_window(app: &AppHandle) -> Option<WebviewWindow> {
+    let window = app.get_focused_window()?;
+    app.get_webview_window(window.label())
+}
+
+// The window a menu action should apply to: the focused one, else main. Nothing
+// has focus when the action comes from the tray or the macOS menu bar with every
+// window hidden, and main is the only window worth acting on then. `None` means
+// the action should be ignored.
+pub fn target_window(app: &AppHandle) -> Option<WebviewWindow> {
+    focused_window(app).or_else(|| app.get_webview_window("main"))
+}
+
+// Raises every open app window, finishing with the one that had focus so it stays
+// on top. Like the system's own Bring All to Front, windows hidden (e.g. to the
+// tray) or minimized stay where they are.
+#[cfg(target_os = "macos")]
+pub fn bring_all_to_front(app: &AppHandle) {
+    let focused = focused_window(app);
+    for window in app.webview_windows().into_values() {
+        if focused.as_ref().is_some_and(|f| f.label() == window.label()) {
+            continue;
+        }
+        if !window.is_visible().unwrap_or(false) || window.is_minimized().unwrap_or(false) {
+            continue;
+        }
+        let _ = window.set_focus();
+    }
+    if let Some(window) = focused {
+        let _ = window.set_focus();
+    }
+}
+
+// One entry in `list_windows`, for a window switcher.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WindowInfo {
+    pub label: String,
+    pub title: String,
+    pub focused: bool,
+}
+
+pub fn list(app: &AppHandle) -> Vec<WindowInfo> {
+    let mut windows: Vec<WindowInfo> = app
+        .webview_windows()
+        .into_values()
+        .map(|window| WindowInfo {
+            label: window.label().to_string(),
+            title: window.title().unwrap_or_default(),
+            focused: window.is_focused().unwrap_or(false),
+        })
+        .collect();
+    windows.sort_by(|a, b| a.label.cmp(&b.label));
+    windows
+}
+
+// Shows, unminimizes and focuses the window with `label`.
+pub fn focus(app: &AppHandle, label: &str) -> Result<(), String> {
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("No open window named \"{}\"", label))?;
+    window.show().map_err(|e| e.to_string())?;
+    window.unminimize().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_windows(app: AppHandle) -> Vec<WindowInfo> {
+    list(&app)
+}
+
+#[tauri::command]
+pub fn focus_window(app: AppHandle, label: String) -> Result<(), String> {
+    focus(&app, &label)
+}
+
+pub const KEEP_ON_TOP_MENU_ID: &str = "keep_on_top";
+
+// Pins the window above others. For the main window the choice is persisted and
+// mirrored in the Window menu's "Keep on Top" item.
+pub fn set_keep_on_top(window: &Window, enabled: bool) -> tauri::Result<()> {
+    window.set_always_on_top(enabled)?;
+    if window.label() == "main" {
+        let app = window.app_handle();
+        if let Err(e) = settings::update(app, |settings| settings.always_on_top = enabled) {
+            log::warn!("Failed to persist keep-on-top: {}", e);
+        }
+        if let Some(item) = menu::check_item(app, KEEP_ON_TOP_MENU_ID) {
+            let _ = item.set_checked(enabled);
+        }
+    }
+    Ok(())
+}
+
+pub fn restore_keep_on_top(window: &WebviewWindow) {
+    if settings::current(window.app_handle()).always_on_top {
+        let _ = window.set_always_on_top(true);
+    }
+}
+
+pub const FULLSCREEN_MENU_ID: &str = "toggle_fullscreen";
+
+// Last known fullscreen and maximized state per window label, to spot changes
+// however they happen. A window's entries go when it's destroyed, so a new window
+// reusing its label starts fresh.
+#[derive(Default)]
+pub struct WindowFlags {
+    fullscreen: Mutex<HashMap<String, bool>>,
+    maximized: Mutex<HashMap<String, bool>>,
+}
+
+// Stores `value` for `window` in the map `flag` picks, returning the previous one.
+// Windows can resize before setup manages the state; nothing listens that early.
+fn note_flag(window: &Window, flag: fn(&WindowFlags) -> &Mutex<HashMap<String, bool>>, value: bool) -> Option<bool> {
+    let flags = window.app_handle().try_state::<WindowFlags>()?;
+    let previous = flag(&flags).lock().unwrap().insert(window.label().to_string(), value);
+    Some(previous.unwrap_or(false))
+}
+
+// Drops a destroyed window's entries.
+pub fn forget_window(window: &Window) {
+    if let Some(flags) = window.app_handle().try_state::<WindowFlags>() {
+        flags.fullscreen.lock().unwrap().remove(window.label());
+        flags.maximized.lock().unwrap().remove(window.label());
+    }
+}
+
+// Records `window`'s fullscreen state, returning the previous one. A change is
+// announced with `window://fullscreen-changed` and reflected in the Window menu.
+fn note_fullscreen(window: &Window, fullscreen: bool) -> bool {
+    let Some(previous) = note_flag(window, |flags| &flags.fullscreen, fullscreen) else { return false };
+    if previous != fullscreen {
+        let _ = window.emit_to(window.label(), events::WINDOW_FULLSCREEN_CHANGED, events::WindowFlagChanged(fullscreen));
+        set_fullscreen_item(window, fullscreen);
+    }
+    previous
+}
+
+// The Window menu's "Full Screen" item follows whichever window has focus.
+fn set_fullscreen_item(window: &Window, fullscreen: bool) {
+    if !window.is_focused().unwrap_or(false) {
+        return;
+    }
+    if let Some(item) = menu::check_item(window.app_handle(), FULLSCREEN_MENU_ID) {
+        let _ = item.set_checked(fullscreen);
+    }
+}
+
+pub fn sync_fullscreen_item(window: &Window) {
+    set_fullscreen_item(window, window.is_fullscreen().unwrap_or(false));
+}
+
+pub fn toggle_fullscreen_window(window: &Window) -> tauri::Result<bool> {
+    let fullscreen = !window.is_fullscreen()?;
+    window.set_fullscreen(fullscreen)?;
+    note_fullscreen(window, fullscreen);
+    Ok(fullscreen)
+}
+
+// Tells the window when it's maximized or restored, however that happened, so a
+// custom titlebar can swap its maximize/restore icon.
+fn note_maximized(window: &Window, maximized: bool) {
+    let Some(previous) = note_flag(window, |flags| &flags.maximized, maximized) else { return };
+    if previous != maximized {
+        let _ = window.emit_to(window.label(), events::WINDOW_MAXIMIZED_CHANGED, events::WindowFlagChanged(maximized));
+    }
+}
+
+// Called on every resize, which is how the OS reports fullscreen and maximize
+// changes made outside the app (e.g. the macOS green button).
+pub fn resized(window: &Window) {
+    note_maximized(window, window.is_maximized().unwrap_or(false));
+    let fullscreen = window.is_fullscreen().unwrap_or(false);
+    let was_fullscreen = note_fullscreen(window, fullscreen);
+    // Some platforms drop always-on-top when a window leaves fullscreen; re-apply it.
+    if window.label() == "main" && was_fullscreen && !fullscreen && settings::current(window.app_handle()).always_on_top {
+        let _ = window.set_always_on_top(true);
+    }
+}
+
+// Returns the new fullscreen state.
+#[tauri::command]
+pub fn toggle_fullscreen(window: Window) -> Result<bool, String> {
+    toggle_fullscreen_window(&window).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_always_on_top(window: Window, enabled: bool) -> Result<(), String> {
+    set_keep_on_top(&window, enabled).map_err(|e| e.to_string())
+}
+
+// Below this a window is too faint to find again.
+const MIN_OPACITY: f64 = 0.2;
+
+// Tauri has no opacity API, so each platform is driven directly. Must run on the
+// main thread.
+#[cfg(target_os = "macos")]
+fn apply_opacity(window: &Window, opacity: f64) -> Result<(), String> {
+    let ns_window = window.ns_window().map_err(|e| e.to_string())?;
+    // SAFETY: the pointer is this window's live NSWindow, used on the main thread.
+    unsafe { (*(ns_window as *const objc2_app_kit::NSWindow)).setAlphaValue(opacity) };
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn apply_opacity(window: &Window, opacity: f64) -> Result<(), String> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE, LWA_ALPHA, WS_EX_LAYERED,
+    };
+    let hwnd = window.hwnd().map_err(|e| e.to_string())?.0 as windows_sys::Win32::Foundation::HWND;
+    // SAFETY: `hwnd` is this window's handle for as long as `window` is alive.
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED as isize);
+        if SetLayeredWindowAttributes(hwnd, 0, (opacity * 255.0).round() as u8, LWA_ALPHA) == 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+    }
+    Ok(())
+}
+
+// Needs a compositing window manager; without one GTK ignores the request.
+#[cfg(target_os = "linux")]
+fn apply_opacity(window: &Window, opacity: f64) -> Result<(), String> {
+    use gtk::prelude::*;
+    let gtk_window = window.gtk_window().map_err(|e| e.to_string())?;
+    if !gtk_window.screen().is_some_and(|screen| screen.is_composited()) {
+        return Err("Window opacity needs a compositing window manager.".into());
+    }
+    gtk_window.set_opacity(opacity);
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn apply_opacity(_window: &Window, _opacity: f64) -> Result<(), String> {
+    Err("Window opacity isn't supported on this platform.".into())
+}
+
+pub fn restore_opacity(window: &Window) {
+    let opacity = settings::current(window.app_handle()).window_opacity.clamp(MIN_OPACITY, 1.0);
+    if opacity < 1.0 {
+        if let Err(e) = apply_opacity(window, opacity) {
+            log::warn!("Failed to restore window opacity: {}", e);
+        }
+    }
+}
+
+// Applies the persisted opacity from any thread, including a return to fully
+// opaque. Where opacity isn't supported there's nothing to undo, so only a failed
+// fade is logged.
+pub fn reapply_opacity(window: &Window) {
+    let opacity = settings::current(window.app_handle()).window_opacity.clamp(MIN_OPACITY, 1.0);
+    let target = window.clone();
+    let result = window.run_on_main_thread(move || {
+        if let Err(e) = apply_opacity(&target, opacity) {
+            if opacity < 1.0 {
+                log::warn!("Failed to apply window opacity: {}", e);
+            }
+        }
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to apply window opacity: {}", e);
+    }
+}
+
+// Fades the whole window, clamped to 0.2–1.0, and returns the opacity applied.
+// The main window's choice is persisted. Async so it can hop to the main thread
+// and wait for the result without blocking it.
+#[tauri::command]
+pub async fn set_window_opacity(window: Window, opacity: f64) -> Result<f64, String> {
+    if !opacity.is_finite() {
+        return Err(format!("Invalid opacity: {}", opacity));
+    }
+    let opacity = opacity.clamp(MIN_OPACITY, 1.0);
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let target = window.clone();
+    window
+        .run_on_main_thread(move || {
+            let _ = tx.send(apply_opacity(&target, opacity));
+        })
+        .map_err(|e| e.to_string())?;
+    rx.await.map_err(|e| e.to_string())??;
+    if window.label() == "main" {
+        if let Err(e) = settings::update(window.app_handle(), |settings| settings.window_opacity = opacity) {
+            log::warn!("Failed to persist window opacity: {}", e);
+        }
+    }
+    Ok(opacity)
+}
+
+// Turns the native frame and titlebar on or off. The main window's choice is
+// persisted. With decorations off, the window is moved by any element marked
+// `data-tauri-drag-region` and the `window_*` commands below stand in for the
+// native buttons.
+pub fn set_window_decorations(window: &Window, decorated: bool) -> tauri::Result<()> {
+    window.set_decorations(decorated)?;
+    if window.label() == "main" {
+        if let Err(e) = settings::update(window.app_handle(), |settings| settings.decorations = decorated) {
+            log::warn!("Failed to persist window decorations: {}", e);
+        }
+    }
+    Ok(())
+}
+
+pub fn restore_decorations(window: &Window) {
+    if !settings::current(window.app_handle()).decorations {
+        let _ = window.set_decorations(false);
+    }
+}
+
+#[tauri::command]
+pub fn set_decorations(window: Window, decorated: bool) -> Result<(), String> {
+    set_window_decorations(&window, decorated).map_err(|e| e.to_string())
+}
+
+// Controls for a custom titlebar. Its drag region should carry
+// `data-tauri-drag-region`, which also gives double-click the platform's own
+// maximize behavior.
+#[tauri::command]
+pub fn window_minimize(window: Window) -> Result<(), String> {
+    window.minimize().map_err(|e| e.to_string())
+}
+
+// Returns whether the window is now maximized.
+#[tauri::command]
+pub fn window_toggle_maximize(window: Window) -> Result<bool, String> {
+    let maximized = !window.is_maximized().map_err(|e| e.to_string())?;
+    let result = if maximized { window.maximize() } else { window.unmaximize() };
+    result.map_err(|e| e.to_string())?;
+    note_maximized(&window, maximized);
+    Ok(maximized)
+}
+
+// Goes through the same close handling as the native button (hide to tray,
+// unsaved-changes prompt).
+#[tauri::command]
+pub fn window_close(window: Window) -> Result<(), String> {
+    window.close().map_err(|e| e.to_string())
+}
+
+// One entry in `list_monitors`; `move_to_monitor` takes its index in that list.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub scale_factor: f64,
+}
+
+#[tauri::command]
+pub fn list_monitors(window: Window) -> Result<Vec<MonitorInfo>, String> {
+    let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+    Ok(monitors
+        .iter()
+        .map(|monitor| MonitorInfo {
+            name: monitor.name().cloned(),
+            width: monitor.size().width,
+            height: monitor.size().height,
+            x: monitor.position().x,
+            y: monitor.position().y,
+            scale_factor: monitor.scale_factor(),
+        })
+        .collect())
+}
+
+// Centers the window on another monitor, shrinking it first if it wouldn't fit.
+// A maximized window is moved at its normal size and maximized again there. The
+// main window's new bounds are saved right away so the next launch opens on the
+// same monitor.
+#[tauri::command]
+pub fn move_to_monitor(window: Window, index: usize) -> Result<(), String> {
+    let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+    let monitor = monitors
+        .get(index)
+        .ok_or_else(|| format!("No monitor at index {} ({} connected)", index, monitors.len()))?;
+    let maximized = window.is_maximized().unwrap_or(false);
+    if maximized {
+        window.unmaximize().map_err(|e| e.to_string())?;
+    }
+
+    let (bounds, origin) = (monitor.size(), monitor.position());
+    let inner = window.inner_size().map_err(|e| e.to_string())?;
+    let outer = window.outer_size().map_err(|e| e.to_string())?;
+    // set_size takes the inner size, so leave room for the frame.
+    let frame = (outer.width.saturating_sub(inner.width), outer.height.saturating_sub(inner.height));
+    let width = inner.width.min(bounds.width.saturating_sub(frame.0));
+    let height = inner.height.min(bounds.height.saturating_sub(frame.1));
+    if (width, height) != (inner.width, inner.height) {
+        window.set_size(PhysicalSize::new(width, height)).map_err(|e| e.to_string())?;
+    }
+    let x = origin.x + (bounds.width.saturating_sub(width + frame.0) / 2) as i32;
+    let y = origin.y + (bounds.height.saturating_sub(height + frame.1) / 2) as i32;
+    window.set_position(PhysicalPosition::new(x, y)).map_err(|e| e.to_string())?;
+
+    if maximized {
+        window.maximize().map_err(|e| e.to_string())?;
+    }
+    if window.label() == "main" {
+        window_state::save(&window);
+    }
+    Ok(())
+}
+
+// macOS has one global menu bar, so hiding it per window is left to Windows/Linux.
+fn apply_menu_visibility(window: &Window, visible: bool) -> tauri::Result<()> {
+    if cfg!(target_os = "macos") {
+        return Ok(());
+    }
+    if visible {
+        window.show_menu()
+    } else {
+        window.hide_menu()
+    }
+}
+
+// Applies the persisted `menu_visible` setting to a newly created window.
+pub fn restore_menu_visibility(window: &Window) {
+    if !settings::current(window.app_handle()).menu_visible {
+        let _ = apply_menu_visibility(window, false);
+    }
+}
+
+// Hides a menu bar shown by `peek_menu` once its window loses focus.
+pub fn end_menu_peek(window: &Window) {
+    if !settings::current(window.app_handle()).menu_visible && window.is_menu_visible().unwrap_or(false) {
+        let _ = apply_menu_visibility(window, false);
+    }
+}
+
+// Shows or hides the menu bar in every window and remembers the choice.
+pub fn set_menu_visibility(app: &AppHandle, visible: bool) -> Result<(), String> {
+    settings::update(app, |settings| settings.menu_visible = visible).map_err(|e| e.to_string())?;
+    for window in app.webview_windows().into_values() {
+        apply_menu_visibility(&window.as_ref().window(), visible).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_menu_visible(window: Window, visible: bool) -> Result<(), String> {
+    set_menu_visibility(window.app_handle(), visible)
+}
+
+// Neither Windows nor GTK bring back a removed menu bar on Alt, so the frontend
+// calls this from its own Alt handler; the bar hides again when focus moves away.
+#[tauri::command]
+pub fn peek_menu(window: Window) -> Result<(), String> {
+    apply_menu_visibility(&window, true).map_err(|e| e.to_string())
+}
+
+// Drops the service worker and its Cache Storage, then reloads, so the page comes
+// back from the app's assets rather than the offline cache. Cookies, localStorage
+// and IndexedDB are left alone. Webviews expose no way to purge just their HTTP
+// cache, so that isn't touched.
+const PURGE_CACHES_AND_RELOAD: &str = r#"
+Promise.all([
+  'caches' in window ? caches.keys().then(keys => Promise.all(keys.map(key => caches.delete(key)))) : null,
+  navigator.serviceWorker ? navigator.serviceWorker.getRegistrations().then(regs => Promise.all(regs.map(reg => reg.unregister()))) : null,
+]).finally(() => location.reload());
+"#;
+
+// Reloads the frontend; `purge_caches` empties its caches first (Force Reload).
+pub fn reload(window: &WebviewWindow, purge_caches: bool) -> tauri::Result<()> {
+    window.eval(if purge_caches { PURGE_CACHES_AND_RELOAD } else { "location.reload()" })
+}
+
+#[cfg(any(debug_assertions, feature = "devtools"))]
+#[tauri::command]
+pub fn toggle_devtools(window: WebviewWindow) {
+    if window.is_devtools_open() {
+        window.close_devtools();
+    } else {
+        window.open_devtools();
+    }
+}
+
+#[cfg(any(debug_assertions, feature = "devtools"))]
+pub fn toggle_target_devtools(app: &AppHandle) {
+    if let Some(window) = target_window(app) {
+        toggle_devtools(window);
+    }
+}
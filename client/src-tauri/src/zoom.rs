@@ -0,0 +1,71 @@
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use crate::{events, menu, settings};
+
+pub const MIN_ZOOM: f64 = 0.5;
+pub const MAX_ZOOM: f64 = 3.0;
+const ZOOM_STEP: f64 = 0.1;
+
+// Clamps to the allowed range and rounds away float drift from repeated steps.
+pub fn clamp(factor: f64) -> f64 {
+    (factor.clamp(MIN_ZOOM, MAX_ZOOM) * 100.0).round() / 100.0
+}
+
+// Greys out the View menu's zoom items that would have no effect at `factor`.
+pub fn sync_menu(app: &AppHandle, factor: f64) {
+    for (id, enabled) in [("zoom_in", factor < MAX_ZOOM), ("zoom_out", factor > MIN_ZOOM), ("zoom_reset", factor != 1.0)] {
+        if let Some(item) = menu::item(app, id) {
+            let _ = item.set_enabled(enabled);
+        }
+    }
+}
+
+// Sets the webview zoom and remembers it for the next launch. The saved level is
+// shared by all windows, so `window://zoom-changed` goes to every window for
+// settings screens to follow.
+pub fn set(window: &WebviewWindow, factor: f64) -> tauri::Result<f64> {
+    let factor = clamp(factor);
+    window.set_zoom(factor)?;
+    let app = window.app_handle();
+    if let Err(e) = settings::update(app, |settings| settings.zoom = factor) {
+        log::warn!("Failed to persist zoom level: {}", e);
+    }
+    sync_menu(app, factor);
+    let _ = app.emit(events::WINDOW_ZOOM_CHANGED, events::ZoomChanged(factor));
+    Ok(factor)
+}
+
+// Steps the window's zoom by `steps` increments; zero resets to actual size.
+pub fn step(window: &WebviewWindow, steps: i32) {
+    let factor = match steps {
+        0 => 1.0,
+        _ => settings::current(window.app_handle()).zoom + ZOOM_STEP * f64::from(steps),
+    };
+    if let Err(e) = set(window, factor) {
+        log::error!("Failed to set zoom: {}", e);
+    }
+}
+
+// Re-applies the saved zoom level during setup.
+pub fn restore(window: &WebviewWindow) {
+    let factor = clamp(settings::current(window.app_handle()).zoom);
+    if let Err(e) = window.set_zoom(factor) {
+        log::warn!("Failed to restore zoom level: {}", e);
+    }
+    sync_menu(window.app_handle(), factor);
+}
+
+// The saved zoom level, which every window applies.
+#[tauri::command]
+pub fn get_zoom(app: AppHandle) -> f64 {
+    clamp(settings::current(&app).zoom)
+}
+
+// Returns the level actually applied after clamping.
+#[tauri::command]
+pub fn set_zoom(window: WebviewWindow, factor: f64) -> Result<f64, String> {
+    if !factor.is_finite() {
+        return Err(format!("Invalid zoom factor: {}", factor));
+    }
+    set(&window, factor).map_err(|e| e.to_string())
+}
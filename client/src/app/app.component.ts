@@ -1,6 +1,8 @@
 import { afterNextRender, ChangeDetectionStrategy, Component, DestroyRef, HostListener, inject, isDevMode, OnInit, PLATFORM_ID, signal } from '@angular/core';
 import { isPlatformBrowser } from '@angular/common';
+import { invoke, isTauri } from '@tauri-apps/api/core';
 import { NavigationEnd, Router, RouterModule } from '@angular/router';
+import { onAction } from '@tauri-apps/plugin-notification';
 
 import { UpdateService } from '@app/services/update.service';
 import { UpdateDialogService } from '@app/services/update-dialog.service';
@@ -96,6 +98,15 @@ export class AppComponent implements OnInit {
       this.resourcePreload.preloadAll();
       this.deepLink.initialize();
 
+      // The Tauri main window starts hidden to avoid a white flash; show it now that we've painted
+      if (isTauri()) {
+        invoke('frontend_ready').catch(() => { /* older native shell without the command */ });
+        // Clicks are routed natively (update notifications start the update flow, others follow their deep link)
+        onAction((notification) => {
+          invoke('notification_actioned', { id: notification.id, extra: notification.extra ?? {} }).catch(() => { /* older native shell */ });
+        }).catch(() => { /* platform doesn't report notification actions */ });
+      }
+
       // Run data migrations after view is ready (so p-toast is mounted)
       // This runs on all platforms: web, Tauri desktop, and mobile
       this.dataMigrationService.runMigrations().then(async () => {
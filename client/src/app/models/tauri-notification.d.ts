@@ -81,4 +81,24 @@ declare module '@tauri-apps/plugin-notification' {
    * }
    */
   export function requestPermission(): Promise<Permission>;
+
+  /**
+   * A notification the user acted on, as delivered to {@link onAction}.
+   *
+   * @property id - The notification's identifier
+   * @property extra - Arbitrary data attached when the notification was sent
+   */
+  export interface ActionedNotification {
+    id: number;
+    extra?: Record<string, unknown>;
+  }
+
+  /**
+   * Registers a listener for notification clicks and actions, including the one that
+   * launched the app, which is delivered once the listener is registered.
+   *
+   * @param callback - Invoked with the actioned notification
+   * @returns Promise resolving to a listener handle with an `unregister` method
+   */
+  export function onAction(callback: (notification: ActionedNotification) => void): Promise<{ unregister(): Promise<void> }>;
 }